@@ -14,3 +14,200 @@ description = Description
 url = Url
 license = License
 size = Size
+source-package = Source package
+source-package-notice = This is a source package. It can't be installed, but its build dependencies can.
+build-depends = Build dependencies
+install-build-depends = Install build dependencies
+recently-failed = Recently failed
+retry-package = Retry { $name }
+reading-package = Reading package information…
+settings = Settings
+installation = Installation
+show-terminal-log = Show log in terminal
+show-terminal-log-description = Offer to follow the dpkg log in a terminal while installing
+no-terminal-found = No supported terminal emulator was found
+confirm-removals-body = Installing would remove the following packages to resolve conflicts:
+install-anyway = Install anyway
+cancel = Cancel
+confirm-install = Confirm installation
+large-install = This will use { $size } of disk space.
+insufficient-space = Not enough disk space: { $size } is needed, but only { $free } is available.
+large-package-threshold = Large install warning
+large-package-threshold-description = Ask before installing packages larger than this
+trust-packages = I trust the source of these packages. Local files are not verified by the system's repositories.
+minimize = Minimize
+minimize-to-tray = Minimize to tray
+minimize-to-tray-description = Show a tray icon with the install progress while minimized
+tray-progress = Installing: { $progress }%
+upgrade-from = Upgrades { $version }
+downgrade-from = Downgrades { $version }
+already-installed = Already installed — nothing to do
+reinstall = Reinstall
+depends = Dependencies
+pre-depends = Pre-dependencies
+pre-depends-risk = Not installed yet: { $depends }. Pre-dependencies must be configured before this package is unpacked, and leaving them unsatisfied can leave the system in a broken state.
+pre-depends-risk-packages = Higher risk: { $packages } have unsatisfied pre-dependencies, which can leave the system in a broken state if the install fails.
+higher-risk = Higher risk
+batch-progress = Package { $current } of { $total }, { $overall }% overall
+batch-dependency = Installing the dependency { $name }
+stop-on-error = Stop on first error
+stop-on-error-description = Skip the remaining packages of a batch after one fails to install
+install-summary = Installation finished
+summary-installed = Installed { $name }
+summary-upgraded = Upgraded { $name } { $from } → { $to }
+summary-downgraded = Downgraded { $name } { $from } → { $to }
+summary-failed = Failed to install { $name }: { $reason }
+summary-skipped = Skipped { $name }
+close = Close
+any-version = any version
+dependency-satisfied = { $name }: { $installed } installed, { $required } required — satisfied
+dependency-unsatisfied = { $name }: { $installed } installed, { $required } required — not satisfied
+dependency-missing = { $name }: not installed, { $required } required
+dependency-provided = { $name }: provided by { $provider } — satisfied
+close-after-install = Close after installing
+close-after-install-description = Close the window once every package installed successfully
+closing-after-install = Installed successfully. Closing…
+reset-after-install = Start over after installing
+reset-after-install-description = Clear the selected files once they installed successfully, ready to open the next one
+minimal-install-ui = Minimal install window
+minimal-install-ui-description = When opening a package from the file manager, show only a small progress window that closes once the install succeeded
+show-full-window = Show details
+history = History
+history-local = Installed from local files
+history-repository = Installed from repositories
+history-empty = Nothing installed yet
+history-installed = Installed
+history-failed = Failed
+bugs = Bug tracker
+vcs-browser = Source repository
+kernel-depends = Kernel dependencies
+kernel-mismatch = This package targets kernel { $targets }, but kernel { $running } is running. It may only take effect after booting into the matching kernel.
+authorization-cancelled = Authorization cancelled
+authorization-not-permitted = You don't have permission to install packages. Ask an administrator to install it for you.
+install-root = Install root
+install-root-description = Directory to install into, such as a chroot. Leave empty for the system root. Only installs that ignore dependencies, which use dpkg, can install into another root.
+install-root-invalid = Not an existing directory
+queue-queued = Queued
+queue-authorizing = Awaiting authorization
+queue-installing = Installing
+queue-finished = Finished
+install-succeeded = Installed
+essential-package = Essential package
+essential-package-notice = This is an essential package. The system relies on it, so it can't be removed once installed.
+essential-removals = This install would remove essential packages: { $packages }. Removing them could leave the system unusable, so the install is blocked.
+removed-packages = Removed { $packages }
+undo = Undo
+maintainer = Maintainer
+copy = Copy
+copied = Copied
+conffiles = Configuration files
+conffiles-description = dpkg keeps local changes to these files on upgrade, and asks what to do when the package changes them too.
+advanced = Advanced
+advanced-mode = I know what I'm doing
+advanced-mode-description = Show options meant for recovering broken systems
+ignore-dependencies = Ignore dependencies
+ignore-dependencies-warning = Packages are installed with dpkg --force-depends, even when their dependencies are missing. This can leave the system broken until the dependencies are installed, and package managers refuse most operations in the meantime.
+queue-busy = Waiting for other operations
+queued-busy = Queued — waiting for other operations to finish
+show-all-fields = Show all fields
+hide-all-fields = Hide all fields
+will-use = Will use ~{ $size }
+unknown-installed-size = Unknown installed size
+estimated-size = { $size } (estimated)
+default-focus = Default button in confirmations
+default-focus-description = Cancel is safer, pressing Enter then won't start the install
+file-dialog = File dialog
+file-dialog-description = The desktop portal matches the rest of your desktop, the native dialog works without one
+file-dialog-auto = Automatic
+file-dialog-portal = Desktop portal
+file-dialog-native = Native
+invalid-package = { $path }: This file is not a valid Debian package or is corrupt
+no-packages-in-directory = { $path }: This folder contains no Debian packages
+busy-automatic-updates = The system is busy with automatic updates. Try again once they have finished.
+busy-package-manager = Another package manager is running. Close it and try again.
+held-changes = You put these packages on hold, but this install would change them anyway: { $packages }
+override-holds = Change held packages anyway
+progress-interval = Progress updates
+progress-interval-description = Update the progress bar less often to reduce distraction and work
+progress-interval-every = Every update
+progress-interval-ms = At most every { $ms } ms
+triggers = Triggers
+triggers-description = dpkg runs the processing behind these triggers, such as rebuilding caches, after the install.
+welcome = Welcome to Wizard
+welcome-body = Wizard installs .deb packages downloaded outside of your software sources, along with the dependencies they need.
+welcome-security = Packages run their install scripts as root and aren't checked by your distribution, so only install files from sources you trust.
+welcome-select-file = To get started, choose Add installers, or open a .deb file with Wizard from your file manager.
+dont-show-again = Don't show this again
+get-started = Get started
+version-local = { $version } (this file)
+version-repository = { $version } ({ $origin })
+post-install-command = Post-install command
+post-install-command-description = Shell command to run after every successful install, such as restarting a service
+post-install-as-root = Run the post-install command as root
+post-install-warning = The command runs exactly as entered, and as root it can change anything on the system. Only enter commands you understand.
+post-install-failed = The post-install command failed: { $reason }
+license-agreement = License agreement
+license-agreement-body = The package asks you to accept its license before it can be installed. Declining cancels the install of this package.
+accept = Accept
+decline = Decline
+preflight-running = Checking whether the packages can be installed…
+check-valid = Valid packages
+check-valid-pass = Every selected file is a valid package
+check-valid-warn = These files aren't valid packages and will be skipped: { $files }
+check-architecture = Architecture
+check-architecture-pass = Every package is built for this system
+check-architecture-fail = Built for an architecture this system doesn't support: { $packages }
+check-architecture-unknown = Couldn't check which architectures this system supports: { $reason }
+enable-architecture = Enable { $architecture } packages
+enable-architecture-description = This system can run { $architecture } packages, but isn't set up to install them yet
+enable-architecture-failed = Failed to enable the architecture: { $reason }
+enable = Enable
+check-space = Disk space
+check-space-pass = Needs { $size }, { $free } available
+check-space-unknown = Needs { $size }, but the available space couldn't be determined
+check-conflicts = Conflicts
+check-conflicts-pass = No installed packages will be removed
+check-conflicts-removals = Conflicts with installed packages, which will be removed: { $packages }
+check-conflicts-unknown = Couldn't work out which installed packages this install would remove, so it may remove some: { $reason }
+check-essential-unknown = Couldn't check whether the packages this install would remove are essential, so the install is blocked: { $reason }
+check-holds-unknown = Couldn't list the packages you put on hold, so this install may change some of them: { $reason }
+check-pre-depends = Pre-dependencies
+check-pre-depends-pass = Every pre-dependency is already installed
+check-daemon = Package manager
+check-daemon-pass = PackageKit is available
+check-daemon-fail = PackageKit isn't running or can't be reached, so nothing can be installed
+check-install-root-pass = Installing into { $root }
+check-install-root-missing = { $root } isn't an existing directory anymore, choose another install root in the settings
+check-install-root-unsupported = PackageKit only installs into the system root, so { $root } can only be used while ignoring dependencies in advanced mode
+check-signatures = Repository signatures
+check-signatures-pass = Every repository package is signed by a trusted key
+check-signatures-unknown = Couldn't check whether the repository packages are signed by a trusted key, so their origin may not be verified: { $reason }
+unauthenticated-packages = These packages come from repositories that aren't signed by a trusted key, so their origin can't be verified: { $packages }
+trust-repositories = I trust these repositories even though their packages can't be verified.
+export-history = Export…
+import-history = Import…
+history-exported = Exported the history to { $path }
+history-export-failed = Couldn't export the history: { $reason }
+history-imported = { $count ->
+    [one] Imported one new entry
+   *[other] Imported { $count } new entries
+}
+history-import-failed = Couldn't import the history: { $reason }
+install-from-list = Install from list…
+list-nothing-to-install = Every package of the list is already installed.
+list-will-install = { $count ->
+    [one] One package will be installed from your software sources.
+   *[other] { $count } packages will be installed from your software sources.
+}
+list-already-installed = Already installed: { $packages }
+list-substituted = Not available at the listed version, the newest version will be installed instead: { $packages }
+list-missing = Not found in your software sources, and won't be installed: { $packages }
+origin = from { $suite }
+origin-ppa = from { $suite }, a personal package archive not reviewed by your distribution
+origin-third-party = from { $suite } at { $url }, a third-party source
+list-installed = { $count ->
+    [one] Installed one package from the list
+   *[other] Installed { $count } packages from the list
+}
+list-install-failed = Couldn't install the packages of the list: { $reason }
+install-cancelled = The install was cancelled
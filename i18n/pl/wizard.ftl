@@ -15,3 +15,203 @@ description = Opis
 url = Link
 license = Licencja
 size = Rozmiar
+source-package = Pakiet źródłowy
+source-package-notice = To jest pakiet źródłowy. Nie można go zainstalować, ale można zainstalować jego zależności budowania.
+build-depends = Zależności budowania
+install-build-depends = Zainstaluj zależności budowania
+recently-failed = Ostatnio nieudane
+retry-package = Ponów { $name }
+reading-package = Odczytywanie informacji o pakiecie…
+settings = Ustawienia
+installation = Instalacja
+show-terminal-log = Pokaż dziennik w terminalu
+show-terminal-log-description = Proponuj śledzenie dziennika dpkg w terminalu podczas instalacji
+no-terminal-found = Nie znaleziono obsługiwanego emulatora terminala
+confirm-removals-body = Instalacja usunie następujące pakiety, aby rozwiązać konflikty:
+install-anyway = Zainstaluj mimo to
+cancel = Anuluj
+confirm-install = Potwierdź instalację
+large-install = Zajmie to { $size } miejsca na dysku.
+insufficient-space = Za mało miejsca na dysku: potrzeba { $size }, a dostępne jest tylko { $free }.
+large-package-threshold = Ostrzeżenie o dużej instalacji
+large-package-threshold-description = Pytaj przed instalacją pakietów większych niż ten rozmiar
+trust-packages = Ufam źródłu tych pakietów. Pliki lokalne nie są weryfikowane przez repozytoria systemu.
+minimize = Minimalizuj
+minimize-to-tray = Minimalizuj do zasobnika
+minimize-to-tray-description = Pokazuj ikonę w zasobniku z postępem instalacji podczas minimalizacji
+tray-progress = Instalowanie: { $progress }%
+upgrade-from = Aktualizuje { $version }
+downgrade-from = Obniża wersję { $version }
+already-installed = Już zainstalowany — nic do zrobienia
+reinstall = Zainstaluj ponownie
+depends = Zależności
+pre-depends = Zależności wstępne
+pre-depends-risk = Jeszcze niezainstalowane: { $depends }. Zależności wstępne muszą zostać skonfigurowane przed rozpakowaniem tego pakietu, a ich brak może pozostawić system w uszkodzonym stanie.
+pre-depends-risk-packages = Większe ryzyko: { $packages } mają niespełnione zależności wstępne, co może pozostawić system w uszkodzonym stanie, jeśli instalacja się nie powiedzie.
+higher-risk = Większe ryzyko
+batch-progress = Pakiet { $current } z { $total }, łącznie { $overall }%
+batch-dependency = Instalowanie zależności { $name }
+stop-on-error = Zatrzymaj przy pierwszym błędzie
+stop-on-error-description = Pomiń pozostałe pakiety, gdy instalacja jednego z nich się nie powiedzie
+install-summary = Instalacja zakończona
+summary-installed = Zainstalowano { $name }
+summary-upgraded = Zaktualizowano { $name } { $from } → { $to }
+summary-downgraded = Obniżono wersję { $name } { $from } → { $to }
+summary-failed = Nie udało się zainstalować { $name }: { $reason }
+summary-skipped = Pominięto { $name }
+close = Zamknij
+any-version = dowolna wersja
+dependency-satisfied = { $name }: zainstalowano { $installed }, wymagane { $required } — spełniona
+dependency-unsatisfied = { $name }: zainstalowano { $installed }, wymagane { $required } — niespełniona
+dependency-missing = { $name }: niezainstalowany, wymagane { $required }
+dependency-provided = { $name }: dostarczany przez { $provider } — spełniona
+close-after-install = Zamknij po instalacji
+close-after-install-description = Zamknij okno, gdy wszystkie pakiety zostaną pomyślnie zainstalowane
+closing-after-install = Zainstalowano pomyślnie. Zamykanie…
+reset-after-install = Zacznij od nowa po instalacji
+reset-after-install-description = Wyczyść wybrane pliki po udanej instalacji, aby od razu otworzyć kolejny
+minimal-install-ui = Minimalne okno instalacji
+minimal-install-ui-description = Przy otwieraniu pakietu z menedżera plików pokazuj tylko małe okno postępu, które zamyka się po udanej instalacji
+show-full-window = Pokaż szczegóły
+history = Historia
+history-local = Zainstalowane z plików lokalnych
+history-repository = Zainstalowane z repozytoriów
+history-empty = Jeszcze nic nie zainstalowano
+history-installed = Zainstalowano
+history-failed = Niepowodzenie
+bugs = Zgłaszanie błędów
+vcs-browser = Repozytorium źródeł
+kernel-depends = Zależności jądra
+kernel-mismatch = Ten pakiet jest przeznaczony dla jądra { $targets }, a uruchomione jest jądro { $running }. Może zadziałać dopiero po uruchomieniu pasującego jądra.
+authorization-cancelled = Anulowano uwierzytelnianie
+authorization-not-permitted = Nie masz uprawnień do instalowania pakietów. Poproś administratora o zainstalowanie pakietu.
+install-root = Katalog główny instalacji
+install-root-description = Katalog, do którego instalować, np. chroot. Pozostaw puste dla głównego katalogu systemu. Tylko instalacje ignorujące zależności, które używają dpkg, mogą instalować do innego katalogu.
+install-root-invalid = To nie jest istniejący katalog
+queue-queued = W kolejce
+queue-authorizing = Oczekuje na uwierzytelnienie
+queue-installing = Instalowanie
+queue-finished = Zakończono
+install-succeeded = Zainstalowano
+essential-package = Pakiet niezbędny
+essential-package-notice = To jest pakiet niezbędny. System od niego zależy, więc po zainstalowaniu nie można go usunąć.
+essential-removals = Ta instalacja usunęłaby niezbędne pakiety: { $packages }. Ich usunięcie mogłoby uniemożliwić korzystanie z systemu, więc instalacja jest zablokowana.
+removed-packages = Usunięto { $packages }
+undo = Cofnij
+maintainer = Opiekun
+copy = Kopiuj
+copied = Skopiowano
+conffiles = Pliki konfiguracyjne
+conffiles-description = dpkg zachowuje lokalne zmiany w tych plikach podczas aktualizacji i pyta, co zrobić, gdy pakiet również je zmienia.
+advanced = Zaawansowane
+advanced-mode = Wiem, co robię
+advanced-mode-description = Pokaż opcje przeznaczone do naprawy uszkodzonych systemów
+ignore-dependencies = Ignoruj zależności
+ignore-dependencies-warning = Pakiety są instalowane za pomocą dpkg --force-depends, nawet gdy brakuje ich zależności. Może to pozostawić system uszkodzony do czasu zainstalowania zależności, a menedżery pakietów w międzyczasie odmawiają większości operacji.
+queue-busy = Oczekuje na inne operacje
+queued-busy = W kolejce — oczekiwanie na zakończenie innych operacji
+show-all-fields = Pokaż wszystkie pola
+hide-all-fields = Ukryj wszystkie pola
+will-use = Zajmie ~{ $size }
+estimated-size = { $size } (szacunkowo)
+unknown-installed-size = Nieznany rozmiar po instalacji
+default-focus = Domyślny przycisk w potwierdzeniach
+default-focus-description = Anuluj jest bezpieczniejsze, naciśnięcie Enter nie rozpocznie wtedy instalacji
+file-dialog = Okno wyboru plików
+file-dialog-description = Portal pulpitu pasuje do reszty pulpitu, a natywne okno działa bez niego
+file-dialog-auto = Automatycznie
+file-dialog-portal = Portal pulpitu
+file-dialog-native = Natywne
+invalid-package = { $path }: Ten plik nie jest prawidłowym pakietem Debiana lub jest uszkodzony
+no-packages-in-directory = { $path }: Ten folder nie zawiera pakietów Debiana
+busy-automatic-updates = System jest zajęty automatycznymi aktualizacjami. Spróbuj ponownie po ich zakończeniu.
+busy-package-manager = Działa inny menedżer pakietów. Zamknij go i spróbuj ponownie.
+held-changes = Te pakiety zostały wstrzymane, ale ta instalacja i tak by je zmieniła: { $packages }
+override-holds = Zmień mimo to wstrzymane pakiety
+progress-interval = Aktualizacje postępu
+progress-interval-description = Aktualizuj pasek postępu rzadziej, aby mniej rozpraszał i obciążał system
+progress-interval-every = Każda aktualizacja
+progress-interval-ms = Najwyżej co { $ms } ms
+triggers = Wyzwalacze
+triggers-description = Po instalacji dpkg wykona przetwarzanie powiązane z tymi wyzwalaczami, np. odbudowę pamięci podręcznych.
+welcome = Witaj w programie Wizard
+welcome-body = Wizard instaluje pakiety .deb pobrane spoza źródeł oprogramowania, razem z potrzebnymi im zależnościami.
+welcome-security = Pakiety uruchamiają swoje skrypty instalacyjne jako root i nie są sprawdzane przez dystrybucję, więc instaluj tylko pliki z zaufanych źródeł.
+welcome-select-file = Aby zacząć, wybierz opcję Dodaj instalator albo otwórz plik .deb programem Wizard z menedżera plików.
+dont-show-again = Nie pokazuj ponownie
+get-started = Zaczynajmy
+version-local = { $version } (ten plik)
+version-repository = { $version } ({ $origin })
+post-install-command = Polecenie po instalacji
+post-install-command-description = Polecenie powłoki uruchamiane po każdej udanej instalacji, np. restartujące usługę
+post-install-as-root = Uruchamiaj polecenie po instalacji jako root
+post-install-warning = Polecenie jest uruchamiane dokładnie tak, jak je wpisano, a jako root może zmienić wszystko w systemie. Wpisuj tylko polecenia, które rozumiesz.
+post-install-failed = Polecenie po instalacji nie powiodło się: { $reason }
+license-agreement = Umowa licencyjna
+license-agreement-body = Pakiet wymaga zaakceptowania licencji przed instalacją. Odrzucenie anuluje instalację tego pakietu.
+accept = Akceptuj
+decline = Odrzuć
+preflight-running = Sprawdzanie, czy pakiety można zainstalować…
+check-valid = Poprawne pakiety
+check-valid-pass = Każdy wybrany plik jest poprawnym pakietem
+check-valid-warn = Te pliki nie są poprawnymi pakietami i zostaną pominięte: { $files }
+check-architecture = Architektura
+check-architecture-pass = Każdy pakiet jest zbudowany dla tego systemu
+check-architecture-fail = Zbudowane dla architektury nieobsługiwanej przez ten system: { $packages }
+check-architecture-unknown = Nie udało się sprawdzić, jakie architektury obsługuje ten system: { $reason }
+enable-architecture = Włącz pakiety { $architecture }
+enable-architecture-description = Ten system może uruchamiać pakiety { $architecture }, ale nie jest jeszcze skonfigurowany do ich instalowania
+enable-architecture-failed = Nie udało się włączyć architektury: { $reason }
+enable = Włącz
+check-space = Miejsce na dysku
+check-space-pass = Wymaga { $size }, dostępne { $free }
+check-space-unknown = Wymaga { $size }, ale nie udało się ustalić dostępnego miejsca
+check-conflicts = Konflikty
+check-conflicts-pass = Żadne zainstalowane pakiety nie zostaną usunięte
+check-conflicts-removals = Konflikt z zainstalowanymi pakietami, które zostaną usunięte: { $packages }
+check-conflicts-unknown = Nie udało się ustalić, które zainstalowane pakiety ta instalacja usunie, więc może usunąć niektóre: { $reason }
+check-essential-unknown = Nie udało się sprawdzić, czy pakiety usuwane przez tę instalację są niezbędne, więc instalacja jest zablokowana: { $reason }
+check-holds-unknown = Nie udało się wyświetlić wstrzymanych pakietów, więc ta instalacja może zmienić niektóre z nich: { $reason }
+check-pre-depends = Zależności wstępne
+check-pre-depends-pass = Każda zależność wstępna jest już zainstalowana
+check-daemon = Menedżer pakietów
+check-daemon-pass = PackageKit jest dostępny
+check-daemon-fail = PackageKit nie działa lub jest nieosiągalny, więc nie można niczego zainstalować
+check-install-root-pass = Instalacja do { $root }
+check-install-root-missing = { $root } nie jest już istniejącym katalogiem, wybierz inny katalog główny instalacji w ustawieniach
+check-install-root-unsupported = PackageKit instaluje tylko do głównego katalogu systemu, więc { $root } można użyć tylko przy ignorowaniu zależności w trybie zaawansowanym
+check-signatures = Podpisy repozytoriów
+check-signatures-pass = Każdy pakiet z repozytorium jest podpisany zaufanym kluczem
+check-signatures-unknown = Nie udało się sprawdzić, czy pakiety z repozytoriów są podpisane zaufanym kluczem, więc ich pochodzenie może nie być zweryfikowane: { $reason }
+unauthenticated-packages = Te pakiety pochodzą z repozytoriów niepodpisanych zaufanym kluczem, więc nie można zweryfikować ich pochodzenia: { $packages }
+trust-repositories = Ufam tym repozytoriom, mimo że ich pakietów nie można zweryfikować.
+export-history = Eksportuj…
+import-history = Importuj…
+history-exported = Wyeksportowano historię do { $path }
+history-export-failed = Nie udało się wyeksportować historii: { $reason }
+history-imported = { $count ->
+    [one] Zaimportowano jeden nowy wpis
+    [few] Zaimportowano { $count } nowe wpisy
+   *[other] Zaimportowano { $count } nowych wpisów
+}
+history-import-failed = Nie udało się zaimportować historii: { $reason }
+install-from-list = Zainstaluj z listy…
+list-nothing-to-install = Wszystkie pakiety z listy są już zainstalowane.
+list-will-install = { $count ->
+    [one] Jeden pakiet zostanie zainstalowany ze źródeł oprogramowania.
+    [few] { $count } pakiety zostaną zainstalowane ze źródeł oprogramowania.
+   *[other] { $count } pakietów zostanie zainstalowanych ze źródeł oprogramowania.
+}
+list-already-installed = Już zainstalowane: { $packages }
+list-substituted = Niedostępne w podanej wersji, zamiast tego zostanie zainstalowana najnowsza: { $packages }
+list-missing = Nie znaleziono w źródłach oprogramowania i nie zostaną zainstalowane: { $packages }
+origin = z { $suite }
+origin-ppa = z { $suite }, osobistego archiwum pakietów niesprawdzanego przez dystrybucję
+origin-third-party = z { $suite } pod adresem { $url }, źródła zewnętrznego
+list-installed = { $count ->
+    [one] Zainstalowano jeden pakiet z listy
+    [few] Zainstalowano { $count } pakiety z listy
+   *[other] Zainstalowano { $count } pakietów z listy
+}
+list-install-failed = Nie udało się zainstalować pakietów z listy: { $reason }
+install-cancelled = Instalacja została anulowana
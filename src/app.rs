@@ -1,27 +1,67 @@
 // SPDX-License-Identifier: {{LICENSE}}
 
-use crate::config::Config;
+use crate::config::{Config, State, LARGE_PACKAGE_THRESHOLDS_MB, PROGRESS_INTERVALS_MS};
+use crate::control::{format_relations, Relation};
+use crate::deb::{self, PackageParseError};
+use crate::debconf;
+use crate::file_dialog::{self, FileDialog};
 use crate::fl;
-use crate::package::{install_packages_local, Package};
-use crate::packagekit::{transaction_handle, PackageKit};
-use ashpd::desktop::file_chooser::{FileFilter, SelectedFiles};
+use crate::history::{self, HistoryEntry};
+use crate::hook;
+use crate::kernel;
+use crate::key_bind::key_binds;
+use crate::package::{
+    enable_architecture, install_build_depends, install_packages_local,
+    install_repository_packages, load_packages, reinstall_candidates, resolve_list, InstallKind,
+    InstallSummary, ListResolution, Package, PackageKind,
+};
+use crate::packagekit::{self, format_size, InstallOptions, ItemProgress};
+use crate::polkit;
+use crate::preflight::{CheckStatus, Preflight};
+use crate::sources::Origin;
+use crate::terminal::Terminal;
+use crate::tray::TrayHandle;
 use cosmic::app::{Command, Core};
 use cosmic::cosmic_config::{self, CosmicConfigEntry};
 use cosmic::iced::alignment::{Horizontal, Vertical};
-use cosmic::iced::{futures, subscription, Alignment, Length, Subscription};
+use cosmic::iced::keyboard::{self, key::Named, Key, Modifiers};
+use cosmic::iced::{
+    clipboard, futures, subscription, window, Alignment, Length, Size, Subscription,
+};
 use cosmic::prelude::CollectionWidget;
 use cosmic::widget::{self, menu, row, settings, ProgressBar};
 use cosmic::{command, cosmic_theme, theme, Application, ApplicationExt, Element};
-use futures_util::SinkExt;
+use futures_util::{SinkExt, StreamExt};
+use once_cell::sync::Lazy;
 use std::any::TypeId;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::env;
-use std::future::pending;
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::fs;
+use std::future::{pending, Future};
+use std::hash::Hash;
+use std::os::unix::net::UnixListener;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 const REPOSITORY: &str = "https://github.com/cosmic-utils/wizard";
+static CONFIRM_BUTTON_ID: Lazy<widget::Id> = Lazy::new(|| widget::Id::new("confirm-install"));
+static CANCEL_BUTTON_ID: Lazy<widget::Id> = Lazy::new(|| widget::Id::new("cancel-install"));
 const APP_ICON: &[u8] = include_bytes!("../res/icons/hicolor/scalable/apps/icon.svg");
+/// How long the install confirmation stays visible before closing automatically.
+const CLOSE_DELAY: Duration = Duration::from_secs(2);
+/// How long the details panel confirms that a field was copied.
+const COPIED_DURATION: Duration = Duration::from_secs(2);
+/// How long removed packages can be restored after an install.
+const UNDO_DURATION: Duration = Duration::from_secs(8);
+/// How long the install button shows a checkmark after a successful install.
+const SUCCESS_INDICATOR_DURATION: Duration = Duration::from_millis(1500);
+/// Size of the window in the minimal install UI.
+const COMPACT_SIZE: Size = Size::new(420.0, 180.0);
+/// Size of the window once the minimal install UI expands to the full app.
+const FULL_SIZE: Size = Size::new(1024.0, 768.0);
 
 /// The application model stores app-specific state used to describe its interface and
 /// drive its logic.
@@ -30,31 +70,190 @@ pub struct AppModel {
     core: Core,
     /// Display a context drawer with the designated page if defined.
     context_page: ContextPage,
+    /// The dialog currently shown on top of the window, if any.
+    dialog: Option<DialogPage>,
     /// Key bindings for the application's menu bar.
     key_binds: HashMap<menu::KeyBind, MenuAction>,
     // Configuration data that persists between application runs.
     config: Config,
+    /// Handle used to write configuration changes.
+    config_handler: Option<cosmic_config::Config>,
+    /// State that persists between application runs, such as the install history.
+    state: State,
+    /// Handle used to write state changes.
+    state_handler: Option<cosmic_config::Config>,
+    /// Terminal emulator used to follow the install log, if one is available.
+    terminal: Option<Terminal>,
+    /// Release of the running kernel, compared against kernel specific packages.
+    kernel_release: Option<String>,
+    /// Install root being edited in the settings page, saved once submitted.
+    install_root_input: String,
+    /// Whether the submitted install root isn't an existing directory.
+    install_root_invalid: bool,
+    /// Post-install command being edited in the settings page, saved once submitted.
+    post_install_input: String,
+    /// Labels for the large package threshold choices in the settings page.
+    threshold_options: Vec<String>,
+    /// Labels for the progress update interval choices in the settings page.
+    progress_interval_options: Vec<String>,
+    /// Labels for the default focus choices in the settings page, Cancel first.
+    focus_options: Vec<String>,
+    /// Labels of [`FileDialog::ALL`].
+    file_dialog_options: Vec<String>,
 
     packages: Vec<Package>,
+    /// Labels of the versions each package can be installed at, keyed by package id,
+    /// the local file first and then its repository versions.
+    version_options: HashMap<String, Vec<String>>,
     package: Option<Package>,
     is_installed: bool,
+    /// Whether the window is about to close after a successful install.
+    closing: bool,
+    /// Whether only the minimal install UI is shown, see [`Config::minimal_install_ui`].
+    compact: bool,
     ask_install: bool,
+    /// The debconf frontend's socket, bound before an install starts and served by the
+    /// debconf subscription while it runs.
+    debconf_listener: Arc<Mutex<Option<UnixListener>>>,
+    /// Whether the install button was pressed and the checks before confirming it run,
+    /// so that repeated presses don't start a second install.
+    install_request: InstallRequest,
+    /// Paths of already installed packages the user explicitly chose to reinstall.
+    reinstall: Vec<String>,
+    /// Pre-flight checklist of the installable packages, once the checks finished.
+    preflight: Option<Preflight>,
+    /// Incremented whenever the installable packages change, so that the results of
+    /// outdated checks are dropped.
+    preflight_generation: u64,
+    /// Whether the user agreed to change packages they put on hold.
+    override_holds: bool,
+    /// Whether dismissing the welcome dialog should keep it from showing again.
+    hide_welcome: bool,
+    /// Whether the user acknowledged that they trust the unauthenticated local files.
+    trust_acknowledged: bool,
+    build_depends: Option<Vec<Vec<Relation>>>,
     progress: Option<f32>,
+    /// The latest progress reported for a single package of the transaction.
+    item_progress: Option<ItemProgress>,
+    /// Names of the selected packages the transaction has reported progress for, in order,
+    /// with the latest percentage of each. Dependencies aren't included.
+    progress_items: Vec<(String, u32)>,
+    /// Whether the window was minimized during an install.
+    minimized: bool,
+    /// The tray icon shown while minimized, once it has started.
+    tray: Option<TrayHandle>,
+    /// Packages that failed to install during this session, offered for a quick retry.
+    recently_failed: Vec<Package>,
+    /// Paths of the files whose metadata is still being read.
+    loading: Vec<String>,
+    /// Whether to briefly show a checkmark after a package installed successfully.
+    show_success: bool,
+    /// Packages the confirmed install is expected to remove.
+    pending_removals: Vec<String>,
+    /// Removed packages that can still be restored, with the repository ids to restore.
+    undo: Option<(Vec<String>, Vec<String>)>,
+    /// Repository packages being reinstalled to undo a removal.
+    restoring: Option<Vec<String>>,
+    /// Repository packages being installed from an imported list.
+    replicating: Option<Vec<String>>,
+    /// Label of the details field that was just copied to the clipboard.
+    copied: Option<String>,
+    /// Whether the details list every control field verbatim.
+    show_all_fields: bool,
+    /// Selected files that turned out not to be valid packages.
+    invalid_files: Vec<String>,
+    /// Directories given on the command line that contain no packages.
+    empty_directories: Vec<String>,
+    /// Outcome of the last history export or import, shown on the history page.
+    history_notice: Option<String>,
+    /// Why the post-install command of the last install failed, if it did.
+    post_install_error: Option<String>,
+    /// Whether a foreign architecture is being enabled for dpkg.
+    enabling_architecture: bool,
+    /// Why enabling a foreign architecture failed, if it did.
+    architecture_error: Option<String>,
 }
 
 /// Messages emitted by the application and its widgets.
 #[derive(Debug, Clone)]
 pub enum Message {
-    OpenRepositoryUrl,
+    OpenUrl(String),
+    Key(Modifiers, Key),
     ToggleContextPage(ContextPage),
     UpdateConfig(Config),
+    SetShowTerminalLog(bool),
+    SetLargePackageThreshold(usize),
+    SetDefaultFocus(usize),
+    SetFileDialog(usize),
+    SetProgressInterval(usize),
+    SetMinimizeToTray(bool),
+    SetStopOnError(bool),
+    SetCloseAfterInstall(bool),
+    SetMinimalInstallUi(bool),
+    SetResetAfterInstall(bool),
+    /// Leave the minimal install UI for the full app.
+    ExpandWindow,
+    InstallRootInput(String),
+    SetAdvancedMode(bool),
+    SetIgnoreDependencies(bool),
+    PostInstallInput(String),
+    SetPostInstallCommand,
+    SetPostInstallAsRoot(bool),
+    PostInstallFinished(Option<String>),
+    SetInstallRoot,
+    Minimize,
+    RestoreWindow,
+    TrayStarted(TrayHandle),
+    OpenTerminalLog,
     SelectFile,
     ProcessSelectedFiles(Vec<String>),
+    /// The packages found in a directory given on the command line.
+    DirectoryScanned(String, Vec<String>),
+    ExportHistory,
+    HistoryExported(Result<String, String>),
+    ImportHistory,
+    HistoryImported(Result<Vec<HistoryEntry>, String>),
+    InstallFromList,
+    ListResolved(Result<Box<ListResolution>, String>),
+    ConfirmListInstall,
+    ListInstalled(Result<Vec<String>, String>),
     UpdatePackages(String),
+    StdinBuffered(Option<PathBuf>),
+    /// The packages read from a file, empty when it couldn't be read.
+    PackagesLoaded(String, Vec<Package>),
+    PackageInvalid(String),
     AskInstallation,
-    Progress(u32),
-    PackagesInstalled(bool),
+    PreflightUpdated(u64, Box<Preflight>),
+    PreflightChecked(Box<Preflight>),
+    ConfirmInstallation,
+    AcknowledgeTrust(bool),
+    HideWelcome(bool),
+    DismissWelcome,
+    OverrideHolds(bool),
+    CloseDialog,
+    LicensePrompt(Arc<debconf::Prompt>),
+    AnswerLicense(bool),
+    Progress(ItemProgress),
+    PackagesInstalled(Box<InstallSummary>),
+    Quit,
+    InstallBuildDepends(Box<Package>),
+    BuildDependsInstalled(Result<Vec<String>, String>),
     ShowDetails(Box<Package>),
+    RetryFailed(String),
+    HideSuccess,
+    UndoAvailable(Vec<String>, Vec<String>),
+    HideUndo,
+    UndoRemoval,
+    RemovalUndone(Result<Vec<String>, String>),
+    CopyField(String, String),
+    ToggleAllFields,
+    HideCopied(String),
+    ToggleReinstall(String),
+    CancelInstall,
+    /// Configure dpkg for a foreign architecture, such as `i386`.
+    EnableArchitecture(String),
+    ArchitectureEnabled(Option<String>),
+    SelectVersion(String, usize),
 }
 
 /// Create a COSMIC application from the app model
@@ -82,39 +281,59 @@ impl Application for AppModel {
     /// Initializes the application with any given flags and startup commands.
     fn init(core: Core, _flags: Self::Flags) -> (Self, Command<Self::Message>) {
         let mut commands = Vec::new();
+        let mut opened_file = false;
 
         for arg in env::args().skip(1) {
+            if arg == "-" {
+                opened_file = true;
+                commands.push(command::future(async {
+                    let result = tokio::task::spawn_blocking(deb::buffer_stdin).await;
+
+                    match result {
+                        Ok(Ok(path)) => Message::StdinBuffered(Some(path)),
+                        Ok(Err(why)) => {
+                            eprintln!("failed to read package from standard input: {why}");
+                            Message::StdinBuffered(None)
+                        }
+                        Err(why) => {
+                            eprintln!("failed to read package from standard input: {why}");
+                            Message::StdinBuffered(None)
+                        }
+                    }
+                }));
+                continue;
+            }
+
             let path = PathBuf::from(&arg);
 
-            if path.is_file() {
+            if path.is_dir() {
+                opened_file = true;
+                commands.push(command::future(async move {
+                    let scan_path = path.clone();
+                    let result =
+                        tokio::task::spawn_blocking(move || deb::packages_in_dir(&scan_path))
+                            .await
+                            .unwrap_or_else(|why| Err(std::io::Error::other(why)));
+
+                    let paths = result.unwrap_or_else(|why| {
+                        eprintln!("failed to read the directory {arg}: {why}");
+                        Vec::new()
+                    });
+                    Message::DirectoryScanned(arg, paths)
+                }));
+            } else if path.is_file() {
+                opened_file = true;
                 commands.push(command::future(async move { Message::UpdatePackages(arg) }))
             }
         }
 
-        // Construct the app model with the runtime core.
-        let mut app = AppModel {
-            core,
-            context_page: ContextPage::default(),
-            key_binds: HashMap::new(),
-            // Optional configuration file for an application.
-            config: cosmic_config::Config::new(Self::APP_ID, Config::VERSION)
-                .map(|context| {
-                    Config::get_entry(&context).unwrap_or_else(|(_errors, config)| {
-                        // for why in errors {
-                        //     tracing::error!(%why, "error loading app config");
-                        // }
-
-                        config
-                    })
-                })
-                .unwrap_or_default(),
-
-            packages: Vec::new(),
-            package: None,
-            is_installed: false,
-            ask_install: false,
-            progress: None,
-        };
+        // Optional configuration file for an application.
+        let config_handler = cosmic_config::Config::new(Self::APP_ID, Config::VERSION).ok();
+        let state_handler = cosmic_config::Config::new_state(Self::APP_ID, State::VERSION).ok();
+        let mut app = AppModel::new(core, config_handler, state_handler, opened_file);
+        if app.compact {
+            commands.push(window::resize(window::Id::MAIN, COMPACT_SIZE));
+        }
 
         // Create a startup command that sets the window title.
         commands.push(app.update_title());
@@ -122,6 +341,11 @@ impl Application for AppModel {
         (app, Command::batch(commands))
     }
 
+    /// Flushes the configuration before the window closes.
+    fn on_close_requested(&self, id: window::Id) -> Option<Self::Message> {
+        (id == window::Id::MAIN).then_some(Message::Quit)
+    }
+
     /// Display a context drawer if the context page is requested.
     fn context_drawer(&self) -> Option<Element<Self::Message>> {
         if !self.core.window.show_context {
@@ -130,16 +354,346 @@ impl Application for AppModel {
 
         Some(match self.context_page {
             ContextPage::About => self.about(),
+            ContextPage::Settings => self.settings(),
+            ContextPage::History => self.history(),
         })
     }
 
+    /// Display a dialog on top of the window if one is requested.
+    fn dialog(&self) -> Option<Element<Self::Message>> {
+        let dialog = match self.dialog.as_ref()? {
+            DialogPage::Welcome => widget::dialog(fl!("welcome"))
+                .control(
+                    widget::column()
+                        .spacing(8)
+                        .push(widget::text(fl!("welcome-body")))
+                        .push(widget::text(fl!("welcome-security")).style(warning_style()))
+                        .push(widget::text(fl!("welcome-select-file")))
+                        .push(
+                            widget::checkbox(fl!("dont-show-again"), self.hide_welcome)
+                                .on_toggle(Message::HideWelcome),
+                        ),
+                )
+                .primary_action(
+                    widget::button::suggested(fl!("get-started")).on_press(Message::DismissWelcome),
+                ),
+            DialogPage::ConfirmInstall(preflight) => {
+                let mut control = widget::column().spacing(8);
+
+                if !preflight.invalid_files.is_empty() {
+                    control = control.push(
+                        widget::text(fl!(
+                            "check-valid-warn",
+                            files = preflight.invalid_files.join(", ")
+                        ))
+                        .style(warning_style()),
+                    );
+                }
+
+                if let Some(root) = preflight.install_root.as_ref().filter(|_| {
+                    preflight.install_root_missing || preflight.install_root_unsupported
+                }) {
+                    let root = root.display().to_string();
+                    control = control.push(
+                        widget::text(if preflight.install_root_missing {
+                            fl!("check-install-root-missing", root = root)
+                        } else {
+                            fl!("check-install-root-unsupported", root = root)
+                        })
+                        .style(error_style()),
+                    );
+                }
+
+                if let Some(free_space) = preflight
+                    .free_space
+                    .filter(|_| preflight.insufficient_space())
+                {
+                    control = control.push(
+                        widget::text(fl!(
+                            "insufficient-space",
+                            size = preflight.size_label(),
+                            free = format_size(free_space)
+                        ))
+                        .style(error_style()),
+                    );
+                } else if preflight.large {
+                    control = control.push(
+                        widget::text(fl!("large-install", size = preflight.size_label()))
+                            .style(warning_style()),
+                    );
+                }
+
+                if !preflight.unsatisfied_pre_depends.is_empty() {
+                    control = control.push(
+                        widget::text(fl!(
+                            "pre-depends-risk-packages",
+                            packages = preflight.unsatisfied_pre_depends.join(", ")
+                        ))
+                        .style(warning_style()),
+                    );
+                }
+
+                if !preflight.essential_removals.is_empty() {
+                    control = control.push(
+                        widget::text(fl!(
+                            "essential-removals",
+                            packages = preflight.essential_removals.join(", ")
+                        ))
+                        .style(error_style()),
+                    );
+                }
+
+                if let Some(why) = &preflight.essential_error {
+                    control = control.push(
+                        widget::text(fl!("check-essential-unknown", reason = why.clone()))
+                            .style(error_style()),
+                    );
+                }
+
+                if let Some(why) = &preflight.simulation_error {
+                    control = control.push(
+                        widget::text(fl!("check-conflicts-unknown", reason = why.clone()))
+                            .style(warning_style()),
+                    );
+                }
+
+                if !preflight.removals.is_empty() {
+                    control = control.push(widget::text(fl!("confirm-removals-body")));
+                    for name in &preflight.removals {
+                        control = control.push(widget::text(name.clone()).style(error_style()));
+                    }
+                }
+
+                if !preflight.held_changes.is_empty() {
+                    control = control.push(
+                        widget::text(fl!(
+                            "held-changes",
+                            packages = preflight.held_changes.join(", ")
+                        ))
+                        .style(warning_style()),
+                    );
+                } else if let Some(why) = &preflight.held_error {
+                    control = control.push(
+                        widget::text(fl!("check-holds-unknown", reason = why.clone()))
+                            .style(warning_style()),
+                    );
+                }
+
+                if preflight.holds_need_override() {
+                    control = control.push(
+                        widget::checkbox(fl!("override-holds"), self.override_holds)
+                            .on_toggle(Message::OverrideHolds),
+                    );
+                }
+
+                if let Some(why) = &preflight.unauthenticated_error {
+                    control = control.push(
+                        widget::text(fl!("check-signatures-unknown", reason = why.clone()))
+                            .style(warning_style()),
+                    );
+                }
+
+                if !preflight.unauthenticated.is_empty() {
+                    control = control.push(
+                        widget::text(fl!(
+                            "unauthenticated-packages",
+                            packages = preflight.unauthenticated.join(", ")
+                        ))
+                        .style(warning_style()),
+                    );
+                }
+
+                control = control.push(
+                    widget::checkbox(fl!("trust-packages"), self.trust_acknowledged)
+                        .on_toggle(Message::AcknowledgeTrust),
+                );
+
+                let install_btn =
+                    if preflight.removals.is_empty() && preflight.simulation_error.is_none() {
+                        widget::button::suggested(fl!("install-file"))
+                    } else {
+                        widget::button::destructive(fl!("install-anyway"))
+                    };
+
+                widget::dialog(fl!("confirm-install"))
+                    .control(control)
+                    .primary_action(
+                        install_btn.id(CONFIRM_BUTTON_ID.clone()).on_press_maybe(
+                            (self.trust_acknowledged
+                                && !preflight.blocked()
+                                && (!preflight.holds_need_override() || self.override_holds))
+                                .then_some(Message::ConfirmInstallation),
+                        ),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel"))
+                            .id(CANCEL_BUTTON_ID.clone())
+                            .on_press(Message::CloseDialog),
+                    )
+            }
+            DialogPage::InstallList(resolution) => {
+                let mut control = widget::column().spacing(8);
+                if resolution.install.is_empty() {
+                    control = control.push(widget::text(fl!("list-nothing-to-install")));
+                } else {
+                    control = control.push(widget::text(fl!(
+                        "list-will-install",
+                        count = resolution.install.len()
+                    )));
+                    let mut packages = widget::column().spacing(4);
+                    for package_id in &resolution.install {
+                        let mut parts = package_id.split(';');
+                        let name = parts.next().unwrap_or_default().to_string();
+                        let version = parts.next().unwrap_or_default().to_string();
+                        packages = packages.push(
+                            row()
+                                .push(widget::text(format!("{name} {version}")))
+                                .push_maybe(resolution.origins.get(package_id).map(origin_label))
+                                .spacing(12),
+                        );
+                    }
+                    control = control.push(packages);
+                }
+                if !resolution.installed.is_empty() {
+                    control = control.push(widget::text::caption(fl!(
+                        "list-already-installed",
+                        packages = resolution.installed.join(", ")
+                    )));
+                }
+                if !resolution.substituted.is_empty() {
+                    control = control.push(
+                        widget::text(fl!(
+                            "list-substituted",
+                            packages = resolution.substituted.join(", ")
+                        ))
+                        .style(warning_style()),
+                    );
+                }
+                if !resolution.missing.is_empty() {
+                    control = control.push(
+                        widget::text(fl!(
+                            "list-missing",
+                            packages = resolution.missing.join(", ")
+                        ))
+                        .style(error_style()),
+                    );
+                }
+                // Same trust gate as for local files, for repositories that can't be
+                // verified, or whose signatures couldn't be checked.
+                let untrusted = !resolution.unauthenticated.is_empty()
+                    || resolution.unauthenticated_error.is_some();
+                if untrusted {
+                    control = control
+                        .push(
+                            widget::text(match &resolution.unauthenticated_error {
+                                Some(why) => {
+                                    fl!("check-signatures-unknown", reason = why.clone())
+                                }
+                                None => fl!(
+                                    "unauthenticated-packages",
+                                    packages = resolution.unauthenticated.join(", ")
+                                ),
+                            })
+                            .style(warning_style()),
+                        )
+                        .push(
+                            widget::checkbox(fl!("trust-repositories"), self.trust_acknowledged)
+                                .on_toggle(Message::AcknowledgeTrust),
+                        );
+                }
+
+                widget::dialog(fl!("install-from-list"))
+                    .control(control)
+                    .primary_action(
+                        widget::button::suggested(fl!("install-file")).on_press_maybe(
+                            (!resolution.install.is_empty()
+                                && (!untrusted || self.trust_acknowledged))
+                                .then_some(Message::ConfirmListInstall),
+                        ),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::CloseDialog),
+                    )
+            }
+            DialogPage::License(prompt) => {
+                let mut control = widget::column().spacing(8);
+                for question in &prompt.questions {
+                    control = control
+                        .push(widget::text::heading(question.description.clone()))
+                        .push(widget::text(question.extended_description.clone()));
+                }
+
+                widget::dialog(fl!("license-agreement"))
+                    .body(fl!("license-agreement-body"))
+                    .control(widget::scrollable(control).height(Length::Fixed(320.0)))
+                    .primary_action(
+                        widget::button::suggested(fl!("accept"))
+                            .on_press(Message::AnswerLicense(true)),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("decline"))
+                            .on_press(Message::AnswerLicense(false)),
+                    )
+            }
+            DialogPage::InstallSummary(summary) => {
+                let mut control = widget::column().spacing(4);
+
+                for package in &summary.installed {
+                    let name = package.name.clone();
+                    control = control.push(widget::text(
+                        match summary.version_changes.get(&package.id) {
+                            Some(change) if change.is_downgrade() => fl!(
+                                "summary-downgraded",
+                                name = name,
+                                from = change.from.clone(),
+                                to = change.to.clone()
+                            ),
+                            Some(change) => fl!(
+                                "summary-upgraded",
+                                name = name,
+                                from = change.from.clone(),
+                                to = change.to.clone()
+                            ),
+                            None => fl!("summary-installed", name = name),
+                        },
+                    ));
+                }
+                for (package, why) in &summary.failed {
+                    control = control.push(widget::text(fl!(
+                        "summary-failed",
+                        name = package.name.clone(),
+                        reason = why.clone()
+                    )));
+                }
+                for package in &summary.skipped {
+                    control = control.push(widget::text(fl!(
+                        "summary-skipped",
+                        name = package.name.clone()
+                    )));
+                }
+
+                widget::dialog(fl!("install-summary"))
+                    .control(control)
+                    .primary_action(
+                        widget::button::standard(fl!("close")).on_press(Message::CloseDialog),
+                    )
+            }
+        };
+
+        Some(dialog.into())
+    }
+
     /// Elements to pack at the start of the header bar.
     fn header_start(&self) -> Vec<Element<Self::Message>> {
         let menu_bar = menu::bar(vec![menu::Tree::with_children(
             menu::root(fl!("view")),
             menu::items(
                 &self.key_binds,
-                vec![menu::Item::Button(fl!("about"), MenuAction::About)],
+                vec![
+                    menu::Item::Button(fl!("history"), MenuAction::History),
+                    menu::Item::Button(fl!("settings"), MenuAction::Settings),
+                    menu::Item::Button(fl!("about"), MenuAction::About),
+                ],
             ),
         )]);
 
@@ -153,10 +707,15 @@ impl Application for AppModel {
     /// beginning of the application, and persist through its lifetime.
     fn subscription(&self) -> Subscription<Self::Message> {
         struct ProgressSubscription;
-
-        let mut subscriptions =
-            vec![self
-                .core()
+        struct BuildDependsSubscription;
+        struct TraySubscription;
+        struct UndoSubscription;
+        struct DebconfSubscription;
+        struct ListSubscription;
+
+        let mut subscriptions = vec![
+            keyboard::on_key_press(|key, modifiers| Some(Message::Key(modifiers, key))),
+            self.core()
                 .watch_config::<Config>(Self::APP_ID)
                 .map(|update| {
                     // for why in update.errors {
@@ -164,44 +723,116 @@ impl Application for AppModel {
                     // }
 
                     Message::UpdateConfig(update.config)
-                })];
+                }),
+        ];
+
+        let progress_interval = Duration::from_millis(self.config.progress_interval_ms);
 
         if self.ask_install {
-            let packages = self.packages.clone();
-            subscriptions.push(subscription::channel(
+            let packages = self.installable_packages();
+            let options = InstallOptions {
+                allow_untrusted: self.trust_acknowledged,
+                root: self.config.install_root(),
+                ignore_dependencies: self.config.ignore_dependencies(),
+                ..InstallOptions::default()
+            };
+            let stop_on_error = self.config.stop_on_error;
+            subscriptions.push(install_subscription(
                 TypeId::of::<ProgressSubscription>(),
+                progress_interval,
+                move |progress| {
+                    Box::new(install_packages_local(
+                        packages,
+                        options,
+                        stop_on_error,
+                        progress,
+                    ))
+                },
+                Message::PackagesInstalled,
+            ));
+        }
+
+        // Package scripts only ask questions while an install runs.
+        if self.install_running() {
+            let debconf_listener = self.debconf_listener.clone();
+            subscriptions.push(subscription::channel(
+                TypeId::of::<DebconfSubscription>(),
                 16,
                 move |msg_tx| async move {
-                    let msg_tx = Arc::new(tokio::sync::Mutex::new(msg_tx));
-
-                    let msg_tx1 = msg_tx.clone();
-                    tokio::task::spawn_blocking(move || {
-                        if let Ok(status) = install_packages_local(
-                            packages,
-                            Box::new(move |progress| {
-                                let _ = futures::executor::block_on(async {
-                                    msg_tx1.lock().await.send(Message::Progress(progress)).await
-                                });
-                            }),
-                        ) {
-                            let msg_tx2 = msg_tx.clone();
-                            let _ = futures::executor::block_on(async {
-                                msg_tx2
-                                    .lock()
-                                    .await
-                                    .send(Message::PackagesInstalled(status))
-                                    .await
-                            });
+                    let listener = debconf_listener.lock().unwrap().take();
+                    match listener {
+                        Some(listener) => {
+                            debconf::serve(listener, |prompt| {
+                                let mut msg_tx = msg_tx.clone();
+                                async move {
+                                    let _ = msg_tx.send(Message::LicensePrompt(prompt)).await;
+                                }
+                            })
+                            .await
                         }
-                    })
-                    .await
-                    .unwrap();
+                        None => eprintln!("the debconf frontend isn't listening"),
+                    }
 
                     pending().await
                 },
             ));
         }
 
+        if self.minimized && self.config.minimize_to_tray {
+            subscriptions.push(subscription::channel(
+                TypeId::of::<TraySubscription>(),
+                16,
+                |mut msg_tx| async move {
+                    let (activate_tx, mut activate_rx) = futures::channel::mpsc::unbounded();
+                    let tray = TrayHandle::spawn(activate_tx);
+                    let _ = msg_tx.send(Message::TrayStarted(tray)).await;
+
+                    while activate_rx.next().await.is_some() {
+                        let _ = msg_tx.send(Message::RestoreWindow).await;
+                    }
+
+                    pending().await
+                },
+            ));
+        }
+
+        if let Some(build_depends) = self.build_depends.clone() {
+            subscriptions.push(install_subscription(
+                TypeId::of::<BuildDependsSubscription>(),
+                progress_interval,
+                move |progress| {
+                    install_build_depends(build_depends, progress)
+                        .map_err(|why| polkit::describe_failure(&why, polkit::INSTALL_ACTION))
+                },
+                Message::BuildDependsInstalled,
+            ));
+        }
+
+        if let Some(package_ids) = self.restoring.clone() {
+            subscriptions.push(install_subscription(
+                TypeId::of::<UndoSubscription>(),
+                progress_interval,
+                move |progress| {
+                    install_repository_packages(package_ids, false, progress)
+                        .map_err(|why| polkit::describe_failure(&why, polkit::INSTALL_ACTION))
+                },
+                Message::RemovalUndone,
+            ));
+        }
+
+        if let Some(package_ids) = self.replicating.clone() {
+            let allow_untrusted = self.trust_acknowledged;
+            subscriptions.push(install_subscription(
+                TypeId::of::<ListSubscription>(),
+                progress_interval,
+                move |progress| {
+                    install_repository_packages(package_ids, allow_untrusted, progress)
+                        .map_err(|why| polkit::describe_failure(&why, polkit::INSTALL_ACTION))
+                },
+                Message::ListInstalled,
+            ));
+        }
+
         Subscription::batch(subscriptions)
     }
 
@@ -211,8 +842,32 @@ impl Application for AppModel {
     /// on the application's async runtime.
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
         match message {
-            Message::OpenRepositoryUrl => {
-                _ = open::that_detached(REPOSITORY);
+            Message::OpenUrl(url) => {
+                if let Err(why) = open::that_detached(&url) {
+                    eprintln!("failed to open {url}: {why}");
+                }
+            }
+
+            Message::Key(modifiers, key) => {
+                // Escape dismisses the dialog first, then cancels a running install, and only
+                // then closes the context drawer. Dismissing a dialog never cancels an install
+                // behind it, so a second press is needed for that.
+                if key == Key::Named(Named::Escape) && modifiers.is_empty() {
+                    if self.dialog.is_some() {
+                        return self.update(Message::CloseDialog);
+                    }
+                    if self.install_running() {
+                        return self.update(Message::CancelInstall);
+                    }
+                    self.core.window.show_context = false;
+                    return Command::none();
+                }
+
+                for (key_bind, action) in &self.key_binds {
+                    if key_bind.matches(modifiers, &key) {
+                        return self.update(menu::action::MenuAction::message(action));
+                    }
+                }
             }
 
             Message::ToggleContextPage(context_page) => {
@@ -230,126 +885,879 @@ impl Application for AppModel {
             }
 
             Message::UpdateConfig(config) => {
+                // The stored root may have been removed since it was set.
+                self.install_root_invalid = config.install_root_missing();
                 self.config = config;
             }
 
-            Message::SelectFile => {
-                let future = async {
-                    if let Ok(request) = SelectedFiles::open_file()
-                        .title("Select Packages to install")
-                        .accept_label("Add")
-                        .multiple(true)
-                        .modal(true)
-                        .filter(
-                            FileFilter::new("*.deb")
-                                .mimetype("application/vnd.debian.binary-package"),
-                        )
-                        .send()
-                        .await
-                    {
-                        if let Ok(file) = request.response() {
-                            let mut paths = Vec::new();
+            Message::SetShowTerminalLog(show) => {
+                self.update_config(|config, handler| config.set_show_terminal_log(handler, show));
+            }
 
-                            for uri in file.uris() {
-                                paths.push(uri.path().to_string())
-                            }
+            Message::SetLargePackageThreshold(index) => {
+                if let Some(&threshold) = LARGE_PACKAGE_THRESHOLDS_MB.get(index) {
+                    self.update_config(|config, handler| {
+                        config.set_large_package_threshold_mb(handler, threshold)
+                    });
+                }
+                return self.refresh_preflight();
+            }
 
-                            return Some(paths);
-                        }
+            Message::SetProgressInterval(index) => {
+                if let Some(&interval) = PROGRESS_INTERVALS_MS.get(index) {
+                    self.update_config(|config, handler| {
+                        config.set_progress_interval_ms(handler, interval)
+                    });
+                }
+            }
+
+            Message::SetDefaultFocus(index) => {
+                self.update_config(|config, handler| config.set_focus_confirm(handler, index == 1));
+            }
+
+            Message::SetFileDialog(index) => {
+                if let Some(&file_dialog) = FileDialog::ALL.get(index) {
+                    self.update_config(|config, handler| {
+                        config.set_file_dialog(handler, file_dialog)
+                    });
+                }
+            }
+
+            Message::SetMinimizeToTray(minimize_to_tray) => {
+                self.update_config(|config, handler| {
+                    config.set_minimize_to_tray(handler, minimize_to_tray)
+                });
+            }
+
+            Message::SetStopOnError(stop_on_error) => {
+                self.update_config(|config, handler| {
+                    config.set_stop_on_error(handler, stop_on_error)
+                });
+            }
+
+            Message::SetCloseAfterInstall(close_after_install) => {
+                self.update_config(|config, handler| {
+                    config.set_close_after_install(handler, close_after_install)
+                });
+            }
+
+            Message::SetMinimalInstallUi(minimal_install_ui) => {
+                self.update_config(|config, handler| {
+                    config.set_minimal_install_ui(handler, minimal_install_ui)
+                });
+            }
+
+            Message::SetResetAfterInstall(reset_after_install) => {
+                self.update_config(|config, handler| {
+                    config.set_reset_after_install(handler, reset_after_install)
+                });
+            }
+
+            Message::ExpandWindow => {
+                if self.compact {
+                    self.compact = false;
+                    return window::resize(window::Id::MAIN, FULL_SIZE);
+                }
+            }
+
+            Message::InstallRootInput(root) => {
+                self.install_root_input = root;
+                self.install_root_invalid = false;
+            }
+
+            Message::SetInstallRoot => {
+                let root = self.install_root_input.trim().to_string();
+                if root.is_empty() || Path::new(&root).is_dir() {
+                    self.update_config(|config, handler| config.set_install_root(handler, root));
+                } else {
+                    self.install_root_invalid = true;
+                }
+                return self.refresh_preflight();
+            }
+
+            Message::SetAdvancedMode(advanced_mode) => {
+                self.update_config(|config, handler| {
+                    config.set_advanced_mode(handler, advanced_mode)
+                });
+                return self.refresh_preflight();
+            }
+
+            Message::SetIgnoreDependencies(ignore_dependencies) => {
+                self.update_config(|config, handler| {
+                    config.set_ignore_dependencies(handler, ignore_dependencies)
+                });
+                return self.refresh_preflight();
+            }
+
+            Message::PostInstallInput(command) => {
+                self.post_install_input = command;
+            }
+
+            Message::SetPostInstallCommand => {
+                let command = self.post_install_input.trim().to_string();
+                self.update_config(|config, handler| {
+                    config.set_post_install_command(handler, command)
+                });
+            }
+
+            Message::SetPostInstallAsRoot(as_root) => {
+                self.update_config(|config, handler| {
+                    config.set_post_install_as_root(handler, as_root)
+                });
+            }
+
+            Message::PostInstallFinished(error) => {
+                self.post_install_error = error;
+            }
+
+            Message::Minimize => {
+                self.minimized = true;
+                return window::minimize(window::Id::MAIN, true);
+            }
+
+            Message::RestoreWindow => {
+                self.restore();
+                return Command::batch(vec![
+                    window::minimize(window::Id::MAIN, false),
+                    window::gain_focus(window::Id::MAIN),
+                ]);
+            }
+
+            Message::TrayStarted(tray) => {
+                if !self.minimized {
+                    tray.shutdown();
+                    return Command::none();
+                }
+                if let Some(progress) = self.progress {
+                    tray.set_progress(progress as u32);
+                }
+                self.tray = Some(tray);
+            }
+
+            Message::OpenTerminalLog => {
+                if let Some(terminal) = self.terminal {
+                    if let Err(why) = terminal.open_log() {
+                        eprintln!("failed to open terminal: {why}");
                     }
+                }
+            }
+
+            Message::SelectFile => {
+                let future = file_dialog::pick_packages(self.config.file_dialog);
+
+                return app_command(future, Message::ProcessSelectedFiles);
+            }
+
+            Message::ExportHistory => {
+                let entries = self.state.history.clone();
+                let dialog = self.config.file_dialog;
+                let future = async move {
+                    let path = file_dialog::save_json(
+                        dialog,
+                        "Export install history",
+                        "Export",
+                        "wizard-history.json",
+                    )
+                    .await?;
+
+                    let result = history::to_json(&entries)
+                        .and_then(|json| Ok(fs::write(&path, json)?))
+                        .map(|()| path)
+                        .map_err(|why| {
+                            eprintln!("failed to export history: {why}");
+                            why.to_string()
+                        });
+                    Some(result)
+                };
 
-                    None
+                return app_command(future, Message::HistoryExported);
+            }
+
+            Message::HistoryExported(result) => {
+                self.history_notice = Some(match result {
+                    Ok(path) => fl!("history-exported", path = path),
+                    Err(why) => fl!("history-export-failed", reason = why),
+                });
+            }
+
+            Message::ImportHistory => {
+                let dialog = self.config.file_dialog;
+                let future = async move {
+                    let path =
+                        file_dialog::open_json(dialog, "Import install history", "Import").await?;
+
+                    let result = fs::read_to_string(&path)
+                        .map_err(anyhow::Error::from)
+                        .and_then(|text| history::from_json(&text))
+                        .map_err(|why| {
+                            eprintln!("failed to import history from {path}: {why}");
+                            why.to_string()
+                        });
+                    Some(result)
                 };
 
-                return Command::perform(future, |paths| {
-                    if let Some(paths) = paths {
-                        return cosmic::app::Message::App(Message::ProcessSelectedFiles(paths));
+                return app_command(future, Message::HistoryImported);
+            }
+
+            Message::HistoryImported(result) => {
+                self.history_notice = Some(match result {
+                    Ok(entries) => {
+                        let mut merged = self.state.history.clone();
+                        let added = history::merge(&mut merged, entries);
+                        self.save_history(merged);
+                        fl!("history-imported", count = added)
                     }
-                    cosmic::app::Message::None
+                    Err(why) => fl!("history-import-failed", reason = why),
                 });
             }
 
+            Message::InstallFromList => {
+                let dialog = self.config.file_dialog;
+                let future = async move {
+                    let path = file_dialog::open_json(
+                        dialog,
+                        "Install packages from an exported history",
+                        "Open",
+                    )
+                    .await?;
+
+                    let result = tokio::task::spawn_blocking(move || {
+                        let text = fs::read_to_string(&path)?;
+                        let requested = history::installed_packages(&history::from_json(&text)?);
+                        resolve_list(requested)
+                    })
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|result| result)
+                    .map(Box::new)
+                    .map_err(|why| {
+                        eprintln!("failed to read the package list: {why}");
+                        why.to_string()
+                    });
+                    Some(result)
+                };
+
+                return app_command(future, Message::ListResolved);
+            }
+
+            Message::ListResolved(result) => match result {
+                Ok(resolution) => {
+                    self.trust_acknowledged = false;
+                    return self.show_dialog(DialogPage::InstallList(resolution));
+                }
+                Err(why) => self.history_notice = Some(fl!("history-import-failed", reason = why)),
+            },
+
+            Message::ConfirmListInstall => {
+                if let Some(DialogPage::InstallList(resolution)) = self.dialog.take() {
+                    if !resolution.install.is_empty() {
+                        self.listen_for_debconf();
+                        self.replicating = Some(resolution.install);
+                    }
+                }
+            }
+
+            Message::ListInstalled(result) => {
+                self.trust_acknowledged = false;
+                match &result {
+                    Ok(package_ids) => {
+                        self.record_history(
+                            package_ids
+                                .iter()
+                                .map(|package_id| HistoryEntry::repository(package_id, true))
+                                .collect(),
+                        );
+                        self.history_notice =
+                            Some(fl!("list-installed", count = package_ids.len()));
+                    }
+                    Err(why) => {
+                        eprintln!("failed to install the package list: {why}");
+                        self.history_notice =
+                            Some(fl!("list-install-failed", reason = why.clone()));
+                    }
+                }
+                self.replicating = None;
+                self.progress = None;
+                self.item_progress = None;
+                self.progress_items = Vec::new();
+                self.restore();
+            }
+
+            Message::DirectoryScanned(dir, paths) => {
+                if paths.is_empty() {
+                    self.empty_directories.push(dir);
+                } else {
+                    return self.update(Message::ProcessSelectedFiles(paths));
+                }
+            }
+
             Message::ProcessSelectedFiles(paths) => {
                 let mut commands = Vec::new();
 
                 for path in paths {
+                    if self.packages.iter().any(|package| package.path == path) {
+                        continue;
+                    }
                     commands.push(command::future(async { Message::UpdatePackages(path) }))
                 }
 
                 return Command::batch(commands);
             }
 
+            Message::StdinBuffered(path) => {
+                if let Some(path) = path {
+                    let arg = path.to_string_lossy().into_owned();
+                    return self.update(Message::UpdatePackages(arg));
+                }
+            }
+
             Message::UpdatePackages(path) => {
-                let pk = PackageKit::new().unwrap();
-                let tx = pk.transaction().unwrap();
+                self.loading.push(path.clone());
+
+                return command::future(async move {
+                    let load_path = path.clone();
+                    let result =
+                        tokio::task::spawn_blocking(move || load_packages(load_path)).await;
+
+                    match result {
+                        Ok(Ok(packages)) => Message::PackagesLoaded(path, packages),
+                        Ok(Err(why))
+                            if why
+                                .downcast_ref::<PackageParseError>()
+                                .is_some_and(PackageParseError::is_corrupt) =>
+                        {
+                            eprintln!("failed to read package: {why}");
+                            Message::PackageInvalid(path)
+                        }
+                        Ok(Err(why)) => {
+                            eprintln!("failed to read package: {why}");
+                            Message::PackagesLoaded(path, Vec::new())
+                        }
+                        Err(why) => {
+                            eprintln!("failed to read package: {why}");
+                            Message::PackagesLoaded(path, Vec::new())
+                        }
+                    }
+                });
+            }
 
-                tx.get_details_local(&[&path]).unwrap();
+            Message::PackagesLoaded(path, packages) => {
+                self.finish_loading(&path);
+                self.invalid_files
+                    .retain(|path| !packages.iter().any(|package| package.path == *path));
+                for package in packages
+                    .iter()
+                    .filter(|package| !package.repository_versions.is_empty())
+                {
+                    let local = fl!("version-local", version = package.version.clone());
+                    let repository = package.repository_versions.iter().map(|package_id| {
+                        let mut parts = package_id.split(';').skip(1);
+                        let version = parts.next().unwrap_or_default().to_string();
+                        let data = parts.nth(1).unwrap_or_default().to_string();
+                        let origin = package
+                            .repository_origins
+                            .get(package_id)
+                            .map_or(data, |origin| origin.suite.clone());
+                        fl!("version-repository", version = version, origin = origin)
+                    });
+                    self.version_options.insert(
+                        package.id.clone(),
+                        std::iter::once(local).chain(repository).collect(),
+                    );
+                }
+                self.packages.extend(packages);
+                return self.refresh_preflight();
+            }
 
-                let tx_details = transaction_handle(tx, |_| {}).unwrap();
+            Message::SelectVersion(package_id, index) => {
+                if let Some(package) = self
+                    .packages
+                    .iter_mut()
+                    .find(|package| package.id == package_id)
+                {
+                    package.repository_id = index
+                        .checked_sub(1)
+                        .and_then(|index| package.repository_versions.get(index))
+                        .cloned();
+                }
+                return self.refresh_preflight();
+            }
 
-                for tx_detail in tx_details {
-                    self.packages.push(Package::new(path.clone(), tx_detail));
+            Message::PackageInvalid(path) => {
+                self.finish_loading(&path);
+                if !self.invalid_files.contains(&path) {
+                    self.invalid_files.push(path);
                 }
+                return self.refresh_preflight();
             }
-            Message::Progress(progress) => {
+
+            Message::Progress(item) => {
+                let progress = item.total_percentage;
+                let selected = self
+                    .installable_packages()
+                    .iter()
+                    .any(|package| package.name == item.name());
+                if selected {
+                    let percentage = item.percentage.min(100);
+                    match self
+                        .progress_items
+                        .iter_mut()
+                        .find(|(name, _)| name == item.name())
+                    {
+                        Some((_, latest)) => *latest = percentage,
+                        None => self
+                            .progress_items
+                            .push((item.name().to_string(), percentage)),
+                    }
+                }
+                self.item_progress = Some(item);
+
                 // Sometimes it returns 101 at the start
                 if progress > 100 {
                     self.progress = Some(0.0);
                 } else {
                     self.progress = Some(progress as f32);
                 }
+
+                if let Some(tray) = &self.tray {
+                    tray.set_progress(progress.min(100));
+                }
             }
             Message::AskInstallation => {
-                self.ask_install = true;
+                if !self
+                    .install_request
+                    .press(self.install_running(), self.dialog.is_some())
+                {
+                    return Command::none();
+                }
+                self.post_install_error = None;
+                if let Some(preflight) = self.preflight.clone() {
+                    return self.update(Message::PreflightChecked(Box::new(preflight)));
+                }
+                return self.run_preflight(Message::PreflightChecked);
             }
 
-            Message::PackagesInstalled(status) => {
-                self.is_installed = status;
-                self.ask_install = false;
-                self.progress = None;
-                if self.is_installed {
-                    self.packages = Vec::new();
-                    self.package = None;
+            Message::PreflightUpdated(generation, preflight) => {
+                if generation == self.preflight_generation {
+                    self.preflight = Some(*preflight);
                 }
             }
 
-            Message::ShowDetails(package) => {
-                if self.package.is_some() {
-                    self.package = None
+            Message::PreflightChecked(preflight) => {
+                // From here on, the confirmation dialog or the running install guard
+                // against repeated presses.
+                self.install_request.settle();
+                self.pending_removals = preflight.removals.clone();
+                self.override_holds = false;
+                if preflight.needs_confirmation() || !self.trust_acknowledged {
+                    let expand = self.show_dialog(DialogPage::ConfirmInstall(*preflight));
+                    let focus = if self.config.focus_confirm {
+                        CONFIRM_BUTTON_ID.clone()
+                    } else {
+                        CANCEL_BUTTON_ID.clone()
+                    };
+                    return Command::batch([expand, widget::button::focus(focus)]);
                 } else {
-                    self.package = Some(*package);
+                    self.listen_for_debconf();
+                    self.ask_install = true;
                 }
             }
-        }
 
-        Command::none()
-    }
+            Message::ConfirmInstallation => {
+                if self.ask_install {
+                    return Command::none();
+                }
+                self.dialog = None;
+                self.listen_for_debconf();
+                self.ask_install = true;
+            }
 
-    /// Describes the interface based on the current state of the application model.
-    ///
-    /// Application events will be processed through the view. Any messages emitted by
-    /// events received by widgets will be passed to the update method.
-    fn view(&self) -> Element<Self::Message> {
-        let filechooser_btn = widget::button::button(
-            widget::text(fl!("select-file")).horizontal_alignment(Horizontal::Center),
+            Message::AcknowledgeTrust(trusted) => {
+                self.trust_acknowledged = trusted;
+            }
+
+            Message::OverrideHolds(override_holds) => {
+                self.override_holds = override_holds;
+            }
+
+            Message::CloseDialog => match self.dialog {
+                Some(DialogPage::Welcome) => return self.update(Message::DismissWelcome),
+                Some(DialogPage::License(_)) => return self.update(Message::AnswerLicense(false)),
+                _ => self.dialog = None,
+            },
+
+            Message::LicensePrompt(prompt) => {
+                return self.show_dialog(DialogPage::License(prompt));
+            }
+
+            Message::AnswerLicense(accepted) => {
+                if let Some(DialogPage::License(prompt)) = self.dialog.take() {
+                    prompt.answer(accepted);
+                }
+            }
+
+            Message::HideWelcome(hide) => {
+                self.hide_welcome = hide;
+            }
+
+            Message::DismissWelcome => {
+                self.dialog = None;
+                if self.hide_welcome {
+                    self.update_config(|config, handler| {
+                        config.set_welcome_dismissed(handler, true)
+                    });
+                }
+            }
+
+            Message::PackagesInstalled(summary) => {
+                self.is_installed = summary.succeeded();
+                self.ask_install = false;
+                self.trust_acknowledged = false;
+                self.progress = None;
+                self.item_progress = None;
+                self.progress_items = Vec::new();
+                self.restore();
+
+                self.record_history(
+                    summary
+                        .installed
+                        .iter()
+                        .map(|package| {
+                            let change = summary.version_changes.get(&package.id);
+                            HistoryEntry::installed(package, true)
+                                .replacing(change.map(|change| change.from.clone()))
+                        })
+                        .chain(summary.failed.iter().map(|(package, why)| {
+                            HistoryEntry::installed(package, false).failed_because(why.clone())
+                        }))
+                        .collect(),
+                );
+
+                let installed: Vec<&str> = summary
+                    .installed
+                    .iter()
+                    .map(|package| package.path.as_str())
+                    .collect();
+                self.recently_failed
+                    .retain(|package| !installed.contains(&package.path.as_str()));
+                self.packages
+                    .retain(|package| !installed.contains(&package.path.as_str()));
+                self.reinstall
+                    .retain(|path| !installed.contains(&path.as_str()));
+                if self
+                    .package
+                    .as_ref()
+                    .is_some_and(|package| installed.contains(&package.path.as_str()))
+                {
+                    self.package = None;
+                }
+
+                for (package, _) in &summary.failed {
+                    if !self
+                        .recently_failed
+                        .iter()
+                        .any(|failed| failed.path == package.path)
+                    {
+                        self.recently_failed.push(package.clone());
+                    }
+                }
+
+                let removed = std::mem::take(&mut self.pending_removals);
+                let undo = (!summary.installed.is_empty() && !removed.is_empty()).then(|| {
+                    command::future(async move {
+                        let names = removed.clone();
+                        let package_ids = tokio::task::spawn_blocking(move || {
+                            reinstall_candidates(&names).unwrap_or_else(|why| {
+                                eprintln!("failed to find packages to undo the removal: {why}");
+                                Vec::new()
+                            })
+                        })
+                        .await
+                        .unwrap_or_default();
+
+                        Message::UndoAvailable(removed, package_ids)
+                    })
+                });
+
+                let single = summary.total() == 1;
+                let close = (self.config.close_after_install || self.compact)
+                    && summary.succeeded()
+                    && self.installable_packages().is_empty();
+
+                if summary.total() > 1
+                    || !summary.succeeded()
+                    || !summary.version_changes.is_empty()
+                {
+                    self.dialog = Some(DialogPage::InstallSummary(summary));
+                }
+
+                if self.is_installed && self.config.reset_after_install && !close {
+                    self.reset();
+                }
+
+                let undo_offered = undo.is_some();
+                let mut commands: Vec<Command<Message>> = undo.into_iter().collect();
+                commands.push(self.refresh_preflight());
+
+                // Failures need the full app to be looked into, and dialogs don't fit the
+                // minimal window.
+                if !self.is_installed || self.dialog.is_some() {
+                    commands.push(self.update(Message::ExpandWindow));
+                }
+
+                if let Some(hook) = self
+                    .config
+                    .post_install_command()
+                    .filter(|_| self.is_installed)
+                {
+                    let hook = hook.to_string();
+                    let as_root = self.config.post_install_as_root;
+                    commands.push(command::future(async move {
+                        let result = tokio::task::spawn_blocking(move || hook::run(&hook, as_root))
+                            .await
+                            .map_err(anyhow::Error::from)
+                            .and_then(|result| result);
+
+                        Message::PostInstallFinished(result.err().map(|why| {
+                            eprintln!("{why}");
+                            why.to_string()
+                        }))
+                    }));
+                }
+
+                // Stay open while removed packages could still be restored.
+                if close && !undo_offered {
+                    self.closing = true;
+                    commands.push(command::future(async {
+                        tokio::time::sleep(CLOSE_DELAY).await;
+                        Message::Quit
+                    }));
+                }
+
+                if self.is_installed && single {
+                    self.show_success = true;
+                    commands.push(command::future(async {
+                        tokio::time::sleep(SUCCESS_INDICATOR_DURATION).await;
+                        Message::HideSuccess
+                    }));
+                }
+
+                return Command::batch(commands);
+            }
+
+            Message::HideSuccess => {
+                self.show_success = false;
+            }
+
+            Message::UndoAvailable(removed, package_ids) => {
+                // Only offer to undo when every removed package can be restored.
+                if !package_ids.is_empty() && package_ids.len() >= removed.len() {
+                    self.undo = Some((removed, package_ids));
+                    return command::future(async {
+                        tokio::time::sleep(UNDO_DURATION).await;
+                        Message::HideUndo
+                    });
+                }
+            }
+
+            Message::CopyField(label, value) => {
+                self.copied = Some(label.clone());
+                return Command::batch([
+                    clipboard::write(value),
+                    command::future(async {
+                        tokio::time::sleep(COPIED_DURATION).await;
+                        Message::HideCopied(label)
+                    }),
+                ]);
+            }
+
+            Message::ToggleAllFields => {
+                self.show_all_fields = !self.show_all_fields;
+            }
+
+            Message::HideCopied(label) => {
+                // A later copy may have replaced the confirmation already.
+                if self.copied.as_ref() == Some(&label) {
+                    self.copied = None;
+                }
+            }
+
+            Message::HideUndo => {
+                self.undo = None;
+            }
+
+            Message::UndoRemoval => {
+                if let Some((_, package_ids)) = self.undo.take() {
+                    self.listen_for_debconf();
+                    self.restoring = Some(package_ids);
+                }
+            }
+
+            Message::RemovalUndone(result) => {
+                match &result {
+                    Ok(package_ids) => self.record_history(
+                        package_ids
+                            .iter()
+                            .map(|package_id| HistoryEntry::repository(package_id, true))
+                            .collect(),
+                    ),
+                    Err(why) => eprintln!("failed to undo the removal: {why}"),
+                }
+                self.restoring = None;
+                self.progress = None;
+                self.item_progress = None;
+                self.progress_items = Vec::new();
+                self.restore();
+            }
+
+            Message::Quit => {
+                self.flush_config();
+                let _ = fs::remove_file(debconf::socket_path());
+                deb::remove_buffered_stdin();
+                return window::close(window::Id::MAIN);
+            }
+
+            Message::InstallBuildDepends(package) => {
+                if let PackageKind::Source { build_depends } = package.kind {
+                    self.listen_for_debconf();
+                    self.build_depends = Some(build_depends);
+                }
+            }
+
+            Message::BuildDependsInstalled(result) => {
+                match &result {
+                    Ok(package_ids) => self.record_history(
+                        package_ids
+                            .iter()
+                            .map(|package_id| HistoryEntry::repository(package_id, true))
+                            .collect(),
+                    ),
+                    Err(why) => eprintln!("failed to install build dependencies: {why}"),
+                }
+                self.is_installed = result.is_ok();
+                self.build_depends = None;
+                self.progress = None;
+                self.item_progress = None;
+                self.progress_items = Vec::new();
+                self.restore();
+            }
+
+            Message::ShowDetails(package) => {
+                if self.package.is_some() {
+                    self.package = None
+                } else {
+                    self.package = Some(*package);
+                }
+            }
+
+            Message::EnableArchitecture(architecture) => {
+                self.enabling_architecture = true;
+                self.architecture_error = None;
+                return command::future(async move {
+                    let result =
+                        tokio::task::spawn_blocking(move || enable_architecture(&architecture))
+                            .await
+                            .map_err(anyhow::Error::from)
+                            .and_then(|result| result);
+
+                    Message::ArchitectureEnabled(result.err().map(|why| {
+                        eprintln!("failed to enable the architecture: {why}");
+                        why.to_string()
+                    }))
+                });
+            }
+
+            Message::ArchitectureEnabled(error) => {
+                self.enabling_architecture = false;
+                self.architecture_error = error;
+                return self.refresh_preflight();
+            }
+
+            Message::CancelInstall => {
+                if self.install_running() {
+                    return Command::perform(
+                        tokio::task::spawn_blocking(|| {
+                            if let Err(why) = packagekit::cancel() {
+                                eprintln!("failed to cancel the install: {why}");
+                            }
+                        }),
+                        |_| cosmic::app::Message::None,
+                    );
+                }
+            }
+
+            Message::ToggleReinstall(path) => {
+                if let Some(index) = self.reinstall.iter().position(|marked| *marked == path) {
+                    self.reinstall.remove(index);
+                } else {
+                    self.reinstall.push(path);
+                }
+                return self.refresh_preflight();
+            }
+
+            Message::RetryFailed(path) => {
+                if !self.packages.iter().any(|package| package.path == path) {
+                    return command::future(async { Message::UpdatePackages(path) });
+                }
+            }
+        }
+
+        Command::none()
+    }
+
+    /// Describes the interface based on the current state of the application model.
+    ///
+    /// Application events will be processed through the view. Any messages emitted by
+    /// events received by widgets will be passed to the update method.
+    fn view(&self) -> Element<Self::Message> {
+        let state = self.view_state();
+        if self.compact {
+            return self.compact_view(state);
+        }
+
+        let filechooser_btn = widget::button::button(
+            widget::text(fl!("select-file")).horizontal_alignment(Horizontal::Center),
         )
         .padding(10)
         .width(Length::FillPortion(1))
         .on_press(Message::SelectFile);
 
-        let install_btn: Option<Element<'_, _>> = if !self.packages.is_empty() {
-            Some(
+        let install_btn: Option<Element<'_, _>> = match state.install_button {
+            InstallButton::Succeeded => Some(
                 widget::button(
-                    widget::text(fl!("install-file")).horizontal_alignment(Horizontal::Center),
+                    widget::row()
+                        .spacing(8)
+                        .align_items(Alignment::Center)
+                        .push(widget::icon::from_name("emblem-ok-symbolic").size(16))
+                        .push(widget::text(fl!("install-succeeded"))),
                 )
                 .padding(10)
                 .width(Length::FillPortion(1))
-                .on_press(Message::AskInstallation)
                 .style(theme::Button::Suggested)
                 .into(),
-            )
-        } else {
-            None
+            ),
+            InstallButton::Enabled | InstallButton::Disabled => {
+                let button = widget::button(
+                    widget::text(fl!("install-file")).horizontal_alignment(Horizontal::Center),
+                )
+                .padding(10)
+                .width(Length::FillPortion(1))
+                .on_press_maybe(
+                    (state.install_button == InstallButton::Enabled)
+                        .then_some(Message::AskInstallation),
+                )
+                .style(theme::Button::Suggested);
+
+                Some(
+                    widget::tooltip(
+                        button,
+                        widget::text(self.installed_size_label()),
+                        widget::tooltip::Position::Bottom,
+                    )
+                    .into(),
+                )
+            }
+            InstallButton::Hidden => None,
         };
         let max_width = if install_btn.is_some() { 800 } else { 400 };
         let header = widget::container(
@@ -366,12 +1774,75 @@ impl Application for AppModel {
 
         let mut files_column = widget::list_column();
 
-        for package in self.packages.clone() {
+        for package in self
+            .packages
+            .iter()
+            .map(|package| self.as_installed(package))
+        {
+            let build_depends_btn: Option<Element<'_, _>> = package.is_source().then(|| {
+                widget::button::standard(fl!("install-build-depends"))
+                    .on_press_maybe(
+                        self.build_depends
+                            .is_none()
+                            .then(|| Message::InstallBuildDepends(Box::new(package.clone()))),
+                    )
+                    .into()
+            });
+            let source_label: Option<Element<'_, _>> = package
+                .is_source()
+                .then(|| widget::text::caption(fl!("source-package")).into());
+            let failed_label: Option<Element<'_, _>> = self
+                .recently_failed
+                .iter()
+                .any(|failed| failed.path == package.path)
+                .then(|| {
+                    widget::text::caption(fl!("recently-failed"))
+                        .style(warning_style())
+                        .into()
+                });
+            let install_kind_label: Option<Element<'_, _>> = self.install_kind_label(&package);
+            let version_dropdown: Option<Element<'_, _>> = self
+                .version_options
+                .get(&package.id)
+                .filter(|_| !self.local_files_only())
+                .map(|options| {
+                    let selected = package.repository_id.as_ref().map_or(Some(0), |id| {
+                        package
+                            .repository_versions
+                            .iter()
+                            .position(|candidate| candidate == id)
+                            .map(|index| index + 1)
+                    });
+                    let package_id = package.id.clone();
+                    widget::dropdown(options, selected, move |index| {
+                        Message::SelectVersion(package_id.clone(), index)
+                    })
+                    .into()
+                });
+            let origin_label: Option<Element<'_, _>> = package.selected_origin().map(origin_label);
+            let essential_label: Option<Element<'_, _>> = package
+                .essential
+                .then(|| widget::text::caption(fl!("essential-package")).into());
+            let risk_label: Option<Element<'_, _>> =
+                (!package.unsatisfied_pre_depends().is_empty()).then(|| {
+                    widget::text::caption(fl!("higher-risk"))
+                        .style(warning_style())
+                        .into()
+                });
+
             files_column = files_column.add(settings::item(
                 package.name.clone(),
                 row()
                     .push(widget::text(package.path.clone()))
+                    .push_maybe(source_label)
+                    .push_maybe(failed_label)
+                    .push_maybe(install_kind_label)
+                    .push_maybe(version_dropdown)
+                    .push_maybe(origin_label)
+                    .push_maybe(essential_label)
+                    .push_maybe(risk_label)
                     .spacing(28)
+                    .push_maybe(build_depends_btn)
                     .push(
                         widget::button::standard(fl!("show-details"))
                             .on_press(Message::ShowDetails(Box::new(package))),
@@ -379,7 +1850,7 @@ impl Application for AppModel {
             ));
         }
 
-        let files: Option<Element<'_, _>> = if !self.packages.is_empty() {
+        let files: Option<Element<'_, _>> = if state.files {
             Some(
                 widget::container(widget::container(files_column).max_width(800))
                     .align_x(Horizontal::Center)
@@ -389,12 +1860,41 @@ impl Application for AppModel {
             None
         };
 
+        let closing: Option<Element<'_, _>> = state
+            .closing
+            .then(|| widget::text(fl!("closing-after-install")).into());
+
+        let undo: Option<Element<'_, _>> = self.undo.as_ref().map(|(removed, _)| {
+            widget::row()
+                .spacing(16)
+                .align_items(Alignment::Center)
+                .push(widget::text(fl!(
+                    "removed-packages",
+                    packages = removed.join(", ")
+                )))
+                .push(widget::button::standard(fl!("undo")).on_press(Message::UndoRemoval))
+                .into()
+        });
+
         let content = widget::column()
             .spacing(16)
-            // .push(header)
-            .push_maybe(self.progress().or(Some(header.into())))
+            .push_maybe(closing)
+            .push_maybe(undo)
+            .push_maybe(self.post_install_error.as_ref().map(|why| {
+                widget::text(fl!("post-install-failed", reason = why.clone()))
+                    .style(warning_style())
+            }))
+            .push_maybe(if state.installing {
+                self.progress()
+            } else {
+                Some(header.into())
+            })
+            .push_maybe(self.retry_failed())
+            .push_maybe(self.invalid_files())
             .push_maybe(files)
-            .push_maybe(self.details());
+            .push_maybe(self.parsing_indicator())
+            .push_maybe(self.preflight_checks())
+            .push_maybe(state.details.then(|| self.details()).flatten());
 
         widget::container(content)
             .width(Length::Fill)
@@ -405,28 +1905,518 @@ impl Application for AppModel {
     }
 }
 
+/// How the install button in the header is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallButton {
+    /// There is nothing to install.
+    Hidden,
+    Enabled,
+    /// The pre-flight checks are still running or failed, or an install was already
+    /// requested.
+    Disabled,
+    /// A package was just installed, so a checkmark is shown briefly.
+    Succeeded,
+}
+
+/// Which parts of the main view are shown, derived from the model so that the branches
+/// taken by [`AppModel::view`] can be checked without rendering any widgets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ViewState {
+    /// Whether the progress of an install replaces the header.
+    pub installing: bool,
+    pub install_button: InstallButton,
+    /// Whether the list of selected files is shown.
+    pub files: bool,
+    /// Whether the details of a package are shown.
+    pub details: bool,
+    /// Whether the window is about to close after a successful install.
+    pub closing: bool,
+}
+
+/// The parts of the model [`ViewState`] is derived from.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ViewInputs<'a> {
+    /// Whether a package was just installed.
+    pub succeeded: bool,
+    /// Whether any selected package can be installed.
+    pub installable: bool,
+    /// Whether an install was requested or is running.
+    pub install_busy: bool,
+    /// The pre-flight checklist, once the checks finished.
+    pub preflight: Option<&'a Preflight>,
+    /// Whether the progress of an install is reported.
+    pub installing: bool,
+    /// Whether any file is selected.
+    pub files: bool,
+    /// Whether a package is selected for its details.
+    pub details: bool,
+    /// Whether the window closes because an install just succeeded.
+    pub closing: bool,
+}
+
+impl ViewState {
+    /// Decides which parts of the main view are shown from `inputs`.
+    pub fn new(inputs: ViewInputs) -> Self {
+        let install_button = if inputs.succeeded {
+            InstallButton::Succeeded
+        } else if !inputs.installable {
+            InstallButton::Hidden
+        } else if !inputs.install_busy
+            && inputs
+                .preflight
+                .is_some_and(|preflight| !preflight.blocked())
+        {
+            InstallButton::Enabled
+        } else {
+            InstallButton::Disabled
+        };
+
+        Self {
+            installing: inputs.installing,
+            install_button,
+            files: inputs.files,
+            details: inputs.details,
+            closing: inputs.closing,
+        }
+    }
+}
+
+/// Guards against repeated presses of the install button starting several installs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InstallRequest {
+    pending: bool,
+}
+
+impl InstallRequest {
+    /// Handles a press of the install button, returning whether it starts the checks
+    /// towards a new install.
+    ///
+    /// Presses are ignored while an earlier one is pending, an install is `running`, or a
+    /// dialog such as the confirmation is open.
+    pub fn press(&mut self, running: bool, dialog_open: bool) -> bool {
+        if self.pending || running || dialog_open {
+            return false;
+        }
+        self.pending = true;
+        true
+    }
+
+    /// Forgets the pending press once the checks finished, from when the confirmation
+    /// dialog or the running install guard against repeated presses.
+    pub fn settle(&mut self) {
+        self.pending = false;
+    }
+
+    /// Whether a press is waiting for the checks to finish.
+    pub fn is_pending(&self) -> bool {
+        self.pending
+    }
+}
+
 impl AppModel {
+    /// Builds the model from the configuration and state stored through their handlers.
+    fn new(
+        core: Core,
+        config_handler: Option<cosmic_config::Config>,
+        state_handler: Option<cosmic_config::Config>,
+        opened_file: bool,
+    ) -> Self {
+        let config = config_handler
+            .as_ref()
+            .map(|context| {
+                Config::get_entry(context).unwrap_or_else(|(_errors, config)| {
+                    // for why in errors {
+                    //     tracing::error!(%why, "error loading app config");
+                    // }
+
+                    config
+                })
+            })
+            .unwrap_or_default();
+
+        let state = state_handler
+            .as_ref()
+            .map(|context| State::get_entry(context).unwrap_or_else(|(_errors, state)| state))
+            .unwrap_or_default();
+
+        // Construct the app model with the runtime core.
+        let install_root_input = config.install_root.clone();
+        let install_root_invalid = config.install_root_missing();
+        let post_install_input = config.post_install_command.clone();
+        let compact = opened_file && config.minimal_install_ui;
+        let dialog = (!config.welcome_dismissed && !compact).then_some(DialogPage::Welcome);
+        AppModel {
+            core,
+            context_page: ContextPage::default(),
+            dialog,
+            key_binds: key_binds(),
+            config,
+            config_handler,
+            state,
+            state_handler,
+            terminal: Terminal::detect(),
+            kernel_release: kernel::running_release(),
+            install_root_input,
+            install_root_invalid,
+            post_install_input,
+            threshold_options: LARGE_PACKAGE_THRESHOLDS_MB
+                .iter()
+                .map(|threshold| format_size(threshold * 1_000_000))
+                .collect(),
+            progress_interval_options: PROGRESS_INTERVALS_MS
+                .iter()
+                .map(|&interval| match interval {
+                    0 => fl!("progress-interval-every"),
+                    interval => fl!("progress-interval-ms", ms = interval),
+                })
+                .collect(),
+            focus_options: vec![fl!("cancel"), fl!("install-file")],
+            file_dialog_options: vec![
+                fl!("file-dialog-auto"),
+                fl!("file-dialog-portal"),
+                fl!("file-dialog-native"),
+            ],
+
+            packages: Vec::new(),
+            version_options: HashMap::new(),
+            package: None,
+            is_installed: false,
+            closing: false,
+            compact,
+            ask_install: false,
+            install_request: InstallRequest::default(),
+            debconf_listener: Arc::new(Mutex::new(None)),
+            reinstall: Vec::new(),
+            hide_welcome: true,
+            trust_acknowledged: false,
+            preflight: None,
+            preflight_generation: 0,
+            override_holds: false,
+            build_depends: None,
+            progress: None,
+            item_progress: None,
+            progress_items: Vec::new(),
+            minimized: false,
+            tray: None,
+            recently_failed: Vec::new(),
+            loading: Vec::new(),
+            show_success: false,
+            pending_removals: Vec::new(),
+            undo: None,
+            restoring: None,
+            replicating: None,
+            copied: None,
+            show_all_fields: false,
+            invalid_files: Vec::new(),
+            empty_directories: Vec::new(),
+            history_notice: None,
+            post_install_error: None,
+            enabling_architecture: false,
+            architecture_error: None,
+        }
+    }
+
+    /// Derives which parts of the main view are shown.
+    pub fn view_state(&self) -> ViewState {
+        ViewState::new(ViewInputs {
+            succeeded: self.show_success,
+            installable: !self.installable_packages().is_empty(),
+            install_busy: self.install_request.is_pending() || self.install_running(),
+            preflight: self.preflight.as_ref(),
+            installing: self.progress.is_some(),
+            files: !self.packages.is_empty(),
+            details: self.package.is_some(),
+            closing: self.closing,
+        })
+    }
+
     /// The about page for this app.
     pub fn about(&self) -> Element<Message> {
         let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
 
-        let icon = widget::svg(widget::svg::Handle::from_memory(APP_ICON));
+        let icon = app_icon();
 
         let title = widget::text::title3(fl!("app-title"));
 
-        let link = widget::button::link(REPOSITORY)
-            .on_press(Message::OpenRepositoryUrl)
-            .padding(0);
-
         widget::column()
             .push(icon)
             .push(title)
-            .push(link)
+            .push(link_button(REPOSITORY.to_string()))
             .align_items(Alignment::Center)
             .spacing(space_xxs)
             .into()
     }
 
+    /// The settings page for this app.
+    pub fn settings(&self) -> Element<Message> {
+        let terminal_description = if self.terminal.is_some() {
+            fl!("show-terminal-log-description")
+        } else {
+            fl!("no-terminal-found")
+        };
+
+        let threshold = LARGE_PACKAGE_THRESHOLDS_MB
+            .iter()
+            .position(|&threshold| threshold == self.config.large_package_threshold_mb);
+
+        let install = settings::view_section(fl!("installation"))
+            .add(
+                settings::item::builder(fl!("show-terminal-log"))
+                    .description(terminal_description)
+                    .toggler(self.config.show_terminal_log, Message::SetShowTerminalLog),
+            )
+            .add(
+                settings::item::builder(fl!("large-package-threshold"))
+                    .description(fl!("large-package-threshold-description"))
+                    .control(widget::dropdown(
+                        &self.threshold_options,
+                        threshold,
+                        Message::SetLargePackageThreshold,
+                    )),
+            )
+            .add(
+                settings::item::builder(fl!("progress-interval"))
+                    .description(fl!("progress-interval-description"))
+                    .control(widget::dropdown(
+                        &self.progress_interval_options,
+                        PROGRESS_INTERVALS_MS
+                            .iter()
+                            .position(|&interval| interval == self.config.progress_interval_ms),
+                        Message::SetProgressInterval,
+                    )),
+            )
+            .add(
+                settings::item::builder(fl!("default-focus"))
+                    .description(fl!("default-focus-description"))
+                    .control(widget::dropdown(
+                        &self.focus_options,
+                        Some(usize::from(self.config.focus_confirm)),
+                        Message::SetDefaultFocus,
+                    )),
+            )
+            .add(
+                settings::item::builder(fl!("file-dialog"))
+                    .description(fl!("file-dialog-description"))
+                    .control(widget::dropdown(
+                        &self.file_dialog_options,
+                        FileDialog::ALL
+                            .iter()
+                            .position(|&file_dialog| file_dialog == self.config.file_dialog),
+                        Message::SetFileDialog,
+                    )),
+            )
+            .add(
+                settings::item::builder(fl!("minimize-to-tray"))
+                    .description(fl!("minimize-to-tray-description"))
+                    .toggler(self.config.minimize_to_tray, Message::SetMinimizeToTray),
+            )
+            .add(
+                settings::item::builder(fl!("stop-on-error"))
+                    .description(fl!("stop-on-error-description"))
+                    .toggler(self.config.stop_on_error, Message::SetStopOnError),
+            )
+            .add(
+                settings::item::builder(fl!("close-after-install"))
+                    .description(fl!("close-after-install-description"))
+                    .toggler(
+                        self.config.close_after_install,
+                        Message::SetCloseAfterInstall,
+                    ),
+            )
+            .add(
+                settings::item::builder(fl!("reset-after-install"))
+                    .description(fl!("reset-after-install-description"))
+                    .toggler(
+                        self.config.reset_after_install,
+                        Message::SetResetAfterInstall,
+                    ),
+            )
+            .add(
+                settings::item::builder(fl!("minimal-install-ui"))
+                    .description(fl!("minimal-install-ui-description"))
+                    .toggler(self.config.minimal_install_ui, Message::SetMinimalInstallUi),
+            )
+            .add(
+                settings::item::builder(fl!("install-root"))
+                    .description(if self.install_root_invalid {
+                        fl!("install-root-invalid")
+                    } else {
+                        fl!("install-root-description")
+                    })
+                    .control(
+                        widget::text_input("/", &self.install_root_input)
+                            .on_input(Message::InstallRootInput)
+                            .on_submit(Message::SetInstallRoot)
+                            .width(Length::Fixed(200.0)),
+                    ),
+            );
+
+        let mut advanced = settings::view_section(fl!("advanced")).add(
+            settings::item::builder(fl!("advanced-mode"))
+                .description(fl!("advanced-mode-description"))
+                .toggler(self.config.advanced_mode, Message::SetAdvancedMode),
+        );
+        if self.config.advanced_mode {
+            advanced = advanced
+                .add(settings::item::builder(fl!("ignore-dependencies")).toggler(
+                    self.config.ignore_dependencies,
+                    Message::SetIgnoreDependencies,
+                ))
+                .add(widget::text(fl!("ignore-dependencies-warning")).style(error_style()))
+                .add(
+                    settings::item::builder(fl!("post-install-command"))
+                        .description(fl!("post-install-command-description"))
+                        .control(
+                            widget::text_input("", &self.post_install_input)
+                                .on_input(Message::PostInstallInput)
+                                .on_submit(Message::SetPostInstallCommand)
+                                .width(Length::Fixed(200.0)),
+                        ),
+                )
+                .add(
+                    settings::item::builder(fl!("post-install-as-root")).toggler(
+                        self.config.post_install_as_root,
+                        Message::SetPostInstallAsRoot,
+                    ),
+                )
+                .add(widget::text(fl!("post-install-warning")).style(error_style()));
+        }
+
+        settings::view_column(vec![install.into(), advanced.into()]).into()
+    }
+
+    /// The install history, with sideloaded packages kept apart from repository ones.
+    pub fn history(&self) -> Element<Message> {
+        let section = |title: String, local: bool| {
+            let entries: Vec<&HistoryEntry> = self
+                .state
+                .history
+                .iter()
+                .rev()
+                .filter(|entry| entry.is_local() == local)
+                .collect();
+
+            let mut section = settings::view_section(title);
+            if entries.is_empty() {
+                section = section.add(widget::text::caption(fl!("history-empty")));
+            }
+            for entry in entries {
+                let outcome = match (entry.succeeded, &entry.notes) {
+                    (true, _) => fl!("history-installed"),
+                    (false, Some(why)) => format!("{}: {why}", fl!("history-failed")),
+                    (false, None) => fl!("history-failed"),
+                };
+                section = section.add(
+                    settings::item::builder(match &entry.previous_version {
+                        Some(previous) => format!("{} {previous} → {}", entry.name, entry.version),
+                        None => format!("{} {}", entry.name, entry.version),
+                    })
+                    .description(format!("{} · {outcome}", entry.date()))
+                    .control(widget::text::caption(entry.architecture.clone())),
+                );
+            }
+            section.into()
+        };
+
+        let actions = widget::row()
+            .spacing(8)
+            .push(
+                widget::button::standard(fl!("export-history")).on_press_maybe(
+                    (!self.state.history.is_empty()).then_some(Message::ExportHistory),
+                ),
+            )
+            .push(widget::button::standard(fl!("import-history")).on_press(Message::ImportHistory))
+            .push(
+                widget::button::standard(fl!("install-from-list")).on_press_maybe(
+                    self.replicating
+                        .is_none()
+                        .then_some(Message::InstallFromList),
+                ),
+            );
+
+        let mut column = widget::column().spacing(8).push(actions);
+        if let Some(notice) = &self.history_notice {
+            column = column.push(widget::text::caption(notice.clone()));
+        }
+
+        settings::view_column(vec![
+            column.into(),
+            section(fl!("history-local"), true),
+            section(fl!("history-repository"), false),
+        ])
+        .into()
+    }
+
+    /// Appends entries to the persistent install history.
+    fn record_history(&mut self, entries: Vec<HistoryEntry>) {
+        if entries.is_empty() {
+            return;
+        }
+
+        let mut history = self.state.history.clone();
+        history.extend(entries);
+        self.save_history(history);
+    }
+
+    /// Replaces the persistent install history.
+    fn save_history(&mut self, history: Vec<HistoryEntry>) {
+        if let Some(handler) = &self.state_handler {
+            if let Err(why) = self.state.set_history(handler, history) {
+                eprintln!("failed to save history: {why}");
+            }
+        } else {
+            self.state.history = history;
+        }
+    }
+
+    /// Forgets that the window was minimized and removes the tray icon.
+    fn restore(&mut self) {
+        self.minimized = false;
+        if let Some(tray) = self.tray.take() {
+            tray.shutdown();
+        }
+    }
+
+    /// Writes a configuration change through the config handler, logging failures.
+    fn update_config(
+        &mut self,
+        update: impl FnOnce(&mut Config, &cosmic_config::Config) -> Result<bool, cosmic_config::Error>,
+    ) {
+        if let Some(handler) = &self.config_handler {
+            if let Err(why) = update(&mut self.config, handler) {
+                eprintln!("failed to save config: {why}");
+            }
+        }
+    }
+
+    /// Writes the whole configuration and state again, so that nothing is lost if an
+    /// earlier write failed or was overwritten by a stale config watcher update.
+    fn flush_config(&self) {
+        write_config(
+            &self.config,
+            self.config_handler.as_ref(),
+            &self.state,
+            self.state_handler.as_ref(),
+        );
+    }
+
+    /// Shows `dialog`, first leaving the minimal install window, which is too small for it.
+    fn show_dialog(&mut self, dialog: DialogPage) -> Command<Message> {
+        self.dialog = Some(dialog);
+        self.update(Message::ExpandWindow)
+    }
+
+    /// Returns to the empty state, as if no file had been selected yet.
+    fn reset(&mut self) {
+        self.packages.clear();
+        self.version_options.clear();
+        self.package = None;
+        self.reinstall.clear();
+        self.invalid_files.clear();
+        self.empty_directories.clear();
+        self.preflight = None;
+    }
+
     /// Updates the header and window titles.
     pub fn update_title(&mut self) -> Command<Message> {
         let window_title = fl!("app-title");
@@ -435,17 +2425,58 @@ impl AppModel {
 
     pub fn details(&self) -> Option<Element<Message>> {
         self.package.clone().map(|package| {
-            let column = widget::list_column()
-                .add(settings::item(fl!("id"), widget::text(package.id)))
-                .add(settings::item(fl!("name"), widget::text(package.name)))
-                .add(settings::item(
-                    fl!("version"),
-                    widget::text(package.version),
-                ))
-                .add(settings::item(
-                    fl!("architecture"),
-                    widget::text(package.architecture),
-                ))
+            let mut column = widget::list_column();
+            let unsatisfied_pre_depends = format_relations(&package.unsatisfied_pre_depends());
+            let pre_depends = (!package.pre_depends.is_empty())
+                .then(|| dependency_list(&package, &package.pre_depends));
+            let depends =
+                (!package.depends.is_empty()).then(|| dependency_list(&package, &package.depends));
+            let kernel_depends: Vec<Vec<Relation>> = package
+                .kernel_depends()
+                .into_iter()
+                .map(<[Relation]>::to_vec)
+                .collect();
+            let mismatched_kernels = self
+                .kernel_release
+                .as_deref()
+                .map(|running| package.mismatched_kernel_releases(running).join(", "))
+                .unwrap_or_default();
+
+            if let PackageKind::Source { build_depends } = &package.kind {
+                column =
+                    column
+                        .add(widget::text(fl!("source-package-notice")))
+                        .add(settings::item(
+                            fl!("build-depends"),
+                            widget::text(format_relations(build_depends)),
+                        ));
+            }
+
+            if package.essential {
+                column = column.add(widget::text(fl!("essential-package-notice")));
+            }
+            if !kernel_depends.is_empty() {
+                column = column.add(settings::item(
+                    fl!("kernel-depends"),
+                    dependency_list(&package, &kernel_depends),
+                ));
+            }
+            if !mismatched_kernels.is_empty() {
+                column = column.add(
+                    widget::text(fl!(
+                        "kernel-mismatch",
+                        targets = mismatched_kernels,
+                        running = self.kernel_release.clone().unwrap_or_default()
+                    ))
+                    .style(warning_style()),
+                );
+            }
+
+            let column = column
+                .add(self.copyable_item(fl!("id"), package.id))
+                .add(self.copyable_item(fl!("name"), package.name))
+                .add(self.copyable_item(fl!("version"), package.version))
+                .add(self.copyable_item(fl!("architecture"), package.architecture))
                 .add(settings::item(
                     fl!("summary"),
                     widget::text(package.summary),
@@ -454,40 +2485,850 @@ impl AppModel {
                     fl!("description"),
                     widget::text(package.description),
                 ))
-                .add(settings::item(fl!("url"), widget::text(package.url)))
                 .add(settings::item(
-                    fl!("license"),
-                    widget::text(package.license),
+                    fl!("url"),
+                    widget::row()
+                        .spacing(8)
+                        .align_items(Alignment::Center)
+                        .push(link_button(package.url.clone()))
+                        .push(self.copy_button(fl!("url"), package.url)),
                 ))
-                .add(settings::item(fl!("size"), widget::text(package.size)));
+                .add(self.copyable_item(fl!("maintainer"), package.maintainer))
+                .add(self.copyable_item(fl!("license"), package.license))
+                .add(settings::item(
+                    fl!("size"),
+                    widget::text(if package.installed_size_estimated {
+                        fl!("estimated-size", size = format_size(package.installed_size))
+                    } else {
+                        package.size
+                    }),
+                ));
+
+            let mut column = column;
+            if let Some(bugs) = package.bugs {
+                column = column.add(settings::item(fl!("bugs"), link_button(bugs)));
+            }
+            if let Some(vcs_browser) = package.vcs_browser {
+                column = column.add(settings::item(fl!("vcs-browser"), link_button(vcs_browser)));
+            }
+            if let Some(pre_depends) = pre_depends {
+                column = column.add(settings::item(fl!("pre-depends"), pre_depends));
+            }
+            if !unsatisfied_pre_depends.is_empty() {
+                column = column.add(
+                    widget::text(fl!("pre-depends-risk", depends = unsatisfied_pre_depends))
+                        .style(warning_style()),
+                );
+            }
+            if let Some(depends) = depends {
+                column = column.add(settings::item(fl!("depends"), depends));
+            }
+            if !package.conffiles.is_empty() {
+                column = column.add(settings::item(
+                    fl!("conffiles"),
+                    widget::tooltip(
+                        widget::text(package.conffiles.join("\n")),
+                        widget::text(fl!("conffiles-description")),
+                        widget::tooltip::Position::Top,
+                    ),
+                ));
+            }
+
+            let (toggle_label, toggle_icon) = if self.show_all_fields {
+                (fl!("hide-all-fields"), "go-up-symbolic")
+            } else {
+                (fl!("show-all-fields"), "go-down-symbolic")
+            };
+            column = column.add(
+                widget::button::text(toggle_label)
+                    .trailing_icon(widget::icon::from_name(toggle_icon).handle())
+                    .on_press(Message::ToggleAllFields),
+            );
+            if self.show_all_fields {
+                if !package.triggers.is_empty() {
+                    column = column.add(settings::item(
+                        fl!("triggers"),
+                        widget::tooltip(
+                            widget::text(package.triggers.join("\n")),
+                            widget::text(fl!("triggers-description")),
+                            widget::tooltip::Position::Top,
+                        ),
+                    ));
+                }
+                for (name, value) in package.control_fields {
+                    column = column.add(settings::item(name, widget::text(value)));
+                }
+            }
+
+            // Long descriptions and dependency lists scroll instead of being clipped.
+            widget::scrollable(
+                widget::container(widget::container(column).max_width(800))
+                    .width(Length::Fill)
+                    .align_x(Horizontal::Center),
+            )
+            .height(Length::Fill)
+            .into()
+        })
+    }
+
+    /// A details row whose value can be copied to the clipboard.
+    fn copyable_item(&self, label: String, value: String) -> Element<Message> {
+        settings::item(
+            label.clone(),
+            widget::row()
+                .spacing(8)
+                .align_items(Alignment::Center)
+                .push(widget::text(value.clone()))
+                .push(self.copy_button(label, value)),
+        )
+        .into()
+    }
+
+    /// A button copying `value` to the clipboard, briefly confirming it was copied.
+    fn copy_button(&self, label: String, value: String) -> Element<Message> {
+        if self.copied.as_ref() == Some(&label) {
+            return widget::text::caption(fl!("copied")).into();
+        }
+
+        widget::button::icon(widget::icon::from_name("edit-copy-symbolic"))
+            .tooltip(fl!("copy"))
+            .on_press(Message::CopyField(label, value))
+            .into()
+    }
+
+    /// Binary packages that would be installed, leaving out those already installed at
+    /// the same version unless the user asked to reinstall them.
+    fn installable_packages(&self) -> Vec<Package> {
+        self.packages
+            .iter()
+            .map(|package| self.as_installed(package))
+            .filter(|package| {
+                !package.is_source()
+                    && (package.install_kind() != InstallKind::AlreadyInstalled
+                        || self.reinstall.contains(&package.path))
+            })
+            .collect()
+    }
 
+    /// Whether the settings force installing the local files with dpkg, so the chosen
+    /// repository versions can't be installed.
+    fn local_files_only(&self) -> bool {
+        self.config.install_root().is_some() || self.config.ignore_dependencies()
+    }
+
+    /// `package` as it will be installed, from its local file when the settings force it.
+    fn as_installed(&self, package: &Package) -> Package {
+        let mut package = package.clone();
+        if self.local_files_only() {
+            package.repository_id = None;
+        }
+        package
+    }
+
+    /// Describes the disk space the installable packages will use.
+    fn installed_size_label(&self) -> String {
+        let packages = self.installable_packages();
+        if packages.iter().any(|package| package.installed_size == 0) {
+            return fl!("unknown-installed-size");
+        }
+
+        let size = format_size(packages.iter().map(|package| package.installed_size).sum());
+        if packages
+            .iter()
+            .any(|package| package.installed_size_estimated)
+        {
+            fl!("will-use", size = fl!("estimated-size", size = size))
+        } else {
+            fl!("will-use", size = size)
+        }
+    }
+
+    /// Describes what installing a package would change, offering to reinstall packages
+    /// that are already installed.
+    fn install_kind_label(&self, package: &Package) -> Option<Element<Message>> {
+        if package.is_source() {
+            return None;
+        }
+
+        let installed = package.installed_version.clone().unwrap_or_default();
+        let label = match package.install_kind() {
+            InstallKind::Install => return None,
+            InstallKind::Upgrade => fl!("upgrade-from", version = installed),
+            InstallKind::Downgrade => fl!("downgrade-from", version = installed),
+            InstallKind::AlreadyInstalled => {
+                let path = package.path.clone();
+                return Some(
+                    widget::row()
+                        .spacing(8)
+                        .align_items(Alignment::Center)
+                        .push(widget::text::caption(fl!("already-installed")))
+                        .push(
+                            widget::checkbox(fl!("reinstall"), self.reinstall.contains(&path))
+                                .on_toggle(move |_| Message::ToggleReinstall(path.clone())),
+                        )
+                        .into(),
+                );
+            }
+        };
+
+        Some(widget::text::caption(label).into())
+    }
+
+    /// Lists the selected files that aren't valid packages, and directories without any.
+    fn invalid_files(&self) -> Option<Element<Message>> {
+        if self.invalid_files.is_empty() && self.empty_directories.is_empty() {
+            return None;
+        }
+
+        let mut column = widget::column().spacing(4);
+        for path in &self.invalid_files {
+            column = column.push(
+                widget::text(fl!("invalid-package", path = path.clone())).style(error_style()),
+            );
+        }
+        for path in &self.empty_directories {
+            column = column.push(
+                widget::text(fl!("no-packages-in-directory", path = path.clone()))
+                    .style(warning_style()),
+            );
+        }
+
+        Some(
             widget::container(widget::container(column).max_width(800))
                 .align_x(Horizontal::Center)
-                .into()
+                .into(),
+        )
+    }
+
+    /// Quick re-select buttons for packages that failed earlier in this session.
+    pub fn retry_failed(&self) -> Option<Element<Message>> {
+        let retryable: Vec<&Package> = self
+            .recently_failed
+            .iter()
+            .filter(|failed| {
+                !self
+                    .packages
+                    .iter()
+                    .any(|package| package.path == failed.path)
+            })
+            .collect();
+
+        if retryable.is_empty() {
+            return None;
+        }
+
+        let mut chips = widget::row()
+            .spacing(8)
+            .align_items(Alignment::Center)
+            .push(widget::text::caption(fl!("recently-failed")));
+
+        for package in retryable {
+            chips = chips.push(
+                widget::button::standard(fl!("retry-package", name = package.name.clone()))
+                    .on_press(Message::RetryFailed(package.path.clone())),
+            );
+        }
+
+        Some(
+            widget::container(widget::container(chips).max_width(800))
+                .align_x(Horizontal::Center)
+                .into(),
+        )
+    }
+
+    /// An inline indicator shown while package metadata is being read, with placeholder
+    /// rows for the files being read.
+    pub fn parsing_indicator(&self) -> Option<Element<Message>> {
+        if self.loading.is_empty() {
+            return None;
+        }
+
+        let indicator = widget::row()
+            .spacing(8)
+            .align_items(Alignment::Center)
+            .push(widget::icon::from_name("process-working-symbolic").size(16))
+            .push(widget::text::caption(fl!("reading-package")));
+
+        // Placeholders for the fields of every file being read, so that the list doesn't
+        // jump once the real values arrive.
+        let mut skeleton = widget::list_column();
+        for path in &self.loading {
+            let name = Path::new(path)
+                .file_name()
+                .map_or_else(|| path.clone(), |name| name.to_string_lossy().into_owned());
+            let fields = [fl!("version"), fl!("architecture"), fl!("size")]
+                .into_iter()
+                .fold(widget::row().spacing(16), |row, label| {
+                    row.push(
+                        widget::row()
+                            .spacing(8)
+                            .align_items(Alignment::Center)
+                            .push(widget::text::caption(label))
+                            .push(skeleton_bar()),
+                    )
+                });
+            skeleton = skeleton.add(settings::item(name, fields));
+        }
+
+        let column = widget::column().spacing(8).push(indicator).push(skeleton);
+
+        Some(
+            widget::container(widget::container(column).max_width(800))
+                .align_x(Horizontal::Center)
+                .into(),
+        )
+    }
+
+    /// Marks one read of `path` as finished.
+    fn finish_loading(&mut self, path: &str) {
+        if let Some(index) = self.loading.iter().position(|loading| loading == path) {
+            self.loading.remove(index);
+        }
+    }
+
+    /// The pre-flight checklist of the installable packages, or an indicator while the
+    /// checks run.
+    fn preflight_checks(&self) -> Option<Element<Message>> {
+        if self.installable_packages().is_empty() || self.progress.is_some() {
+            return None;
+        }
+
+        let Some(preflight) = &self.preflight else {
+            let indicator = widget::row()
+                .spacing(8)
+                .align_items(Alignment::Center)
+                .push(widget::icon::from_name("process-working-symbolic").size(16))
+                .push(widget::text::caption(fl!("preflight-running")));
+
+            return Some(
+                widget::container(widget::container(indicator).max_width(800))
+                    .align_x(Horizontal::Center)
+                    .into(),
+            );
+        };
+
+        let mut checks = widget::list_column();
+        for check in preflight.checks() {
+            let (icon, style) = match check.status {
+                CheckStatus::Pass => ("emblem-ok-symbolic", theme::Text::Default),
+                CheckStatus::Warn | CheckStatus::Unknown => {
+                    ("dialog-warning-symbolic", warning_style())
+                }
+                CheckStatus::Fail => ("dialog-error-symbolic", error_style()),
+            };
+            checks = checks.add(settings::item(
+                check.label,
+                widget::row()
+                    .spacing(8)
+                    .align_items(Alignment::Center)
+                    .push(widget::text::caption(check.detail).style(style))
+                    .push(widget::icon::from_name(icon).size(16)),
+            ));
+        }
+
+        // Multiarch is off by default, which is the usual reason 32-bit packages fail.
+        for architecture in &preflight.enableable_architectures {
+            let description = match &self.architecture_error {
+                Some(why) => {
+                    widget::text::caption(fl!("enable-architecture-failed", reason = why.clone()))
+                        .style(error_style())
+                }
+                None => widget::text::caption(fl!(
+                    "enable-architecture-description",
+                    architecture = architecture.clone()
+                )),
+            };
+            checks = checks.add(settings::item(
+                fl!("enable-architecture", architecture = architecture.clone()),
+                widget::row()
+                    .spacing(8)
+                    .align_items(Alignment::Center)
+                    .push(description)
+                    .push(
+                        widget::button::standard(fl!("enable")).on_press_maybe(
+                            (!self.enabling_architecture)
+                                .then(|| Message::EnableArchitecture(architecture.clone())),
+                        ),
+                    ),
+            ));
+        }
+
+        Some(
+            widget::container(widget::container(checks).max_width(800))
+                .align_x(Horizontal::Center)
+                .into(),
+        )
+    }
+
+    /// Binds the debconf frontend's socket before an install starts, so that the
+    /// transaction finds it, unless it's already listening for a running install.
+    fn listen_for_debconf(&mut self) {
+        if self.install_running() {
+            return;
+        }
+
+        match debconf::bind() {
+            Ok(listener) => *self.debconf_listener.lock().unwrap() = Some(listener),
+            Err(why) => eprintln!("failed to start the debconf frontend: {why}"),
+        }
+    }
+
+    /// Whether an install transaction is running, or about to.
+    fn install_running(&self) -> bool {
+        self.ask_install
+            || self.build_depends.is_some()
+            || self.restoring.is_some()
+            || self.replicating.is_some()
+    }
+
+    /// Runs the pre-flight checks for the installable packages in the background.
+    fn run_preflight(
+        &self,
+        on_finish: impl FnOnce(Box<Preflight>) -> Message + Send + 'static,
+    ) -> Command<Message> {
+        let packages = self.installable_packages();
+        let invalid_files = self.invalid_files.clone();
+        let size_threshold = self.config.large_package_threshold_mb * 1_000_000;
+        let install_root = self.config.install_root();
+        let ignore_dependencies = self.config.ignore_dependencies();
+
+        command::future(async move {
+            let preflight = tokio::task::spawn_blocking(move || {
+                Preflight::run(
+                    packages,
+                    invalid_files,
+                    size_threshold,
+                    install_root,
+                    ignore_dependencies,
+                )
+            })
+            .await
+            .unwrap_or_default();
+
+            on_finish(Box::new(preflight))
         })
     }
+
+    /// Forgets the checklist and runs the checks again, after the packages changed.
+    fn refresh_preflight(&mut self) -> Command<Message> {
+        self.preflight = None;
+        self.preflight_generation += 1;
+        if self.installable_packages().is_empty() {
+            return Command::none();
+        }
+
+        let generation = self.preflight_generation;
+        self.run_preflight(move |preflight| Message::PreflightUpdated(generation, preflight))
+    }
+
+    /// Overall progress of a batch install, such as "Package 3 of 7, 58% overall".
+    ///
+    /// The overall percentage counts every selected package before the current one as
+    /// complete, plus the progress of the current one. Dependencies pulled in by the
+    /// transaction are shown on a line of their own instead of counting as packages.
+    fn batch_progress(&self) -> Option<Element<Message>> {
+        let item = self.item_progress.as_ref()?;
+        let total = self.installable_packages().len();
+        if total < 2 {
+            return None;
+        }
+
+        let (completed, percentage) = match self.progress_items.split_last() {
+            Some(((_, percentage), before)) => (before.len(), *percentage as usize),
+            None => (0, 0),
+        };
+        let overall = (completed * 100 + percentage) / total;
+
+        let is_dependency = !item.name().is_empty()
+            && !self
+                .progress_items
+                .iter()
+                .any(|(name, _)| name == item.name());
+        let dependency: Option<Element<'_, _>> = is_dependency
+            .then(|| widget::text::caption(fl!("batch-dependency", name = item.name())).into());
+
+        Some(
+            widget::column()
+                .spacing(4)
+                .align_items(Alignment::Center)
+                .push(widget::text::caption(fl!(
+                    "batch-progress",
+                    current = completed + 1,
+                    total = total,
+                    overall = overall
+                )))
+                .push_maybe(dependency)
+                .push(self.install_queue(item))
+                .into(),
+        )
+    }
+
+    /// Lists the packages of a batch install with whether they are queued, awaiting
+    /// authorization, installing or finished.
+    fn install_queue(&self, item: &ItemProgress) -> Element<Message> {
+        let packages = self.installable_packages();
+        let current = packages.iter().rposition(|package| {
+            self.progress_items
+                .iter()
+                .any(|(name, _)| *name == package.name)
+        });
+
+        let mut column = widget::column().spacing(2);
+        for (index, package) in packages.iter().enumerate() {
+            let state = match current {
+                Some(current) if index < current => fl!("queue-finished"),
+                Some(current) if index == current => {
+                    if item.name() == package.name && item.is_waiting_for_auth() {
+                        fl!("queue-authorizing")
+                    } else if item.is_queued() {
+                        fl!("queue-busy")
+                    } else {
+                        fl!("queue-installing")
+                    }
+                }
+                _ => fl!("queue-queued"),
+            };
+            column = column.push(widget::text::caption(format!(
+                "{} {} · {state}",
+                package.name, package.version
+            )));
+        }
+
+        column.into()
+    }
+
     pub fn progress(&self) -> Option<Element<Message>> {
         self.progress.map(|progress| {
-            widget::container(
-                widget::container(ProgressBar::new(0.0..=100.0, progress)).max_width(800),
+            let terminal_btn: Option<Element<'_, _>> = self
+                .terminal
+                .filter(|_| self.config.show_terminal_log)
+                .map(|_| {
+                    widget::button::standard(fl!("show-terminal-log"))
+                        .on_press(Message::OpenTerminalLog)
+                        .into()
+                });
+
+            let queued: Option<Element<'_, _>> = self
+                .item_progress
+                .as_ref()
+                .filter(|item| item.is_queued())
+                .map(|_| {
+                    widget::text(fl!("queued-busy"))
+                        .style(warning_style())
+                        .into()
+                });
+
+            let buttons = widget::row()
+                .spacing(8)
+                .push(widget::button::standard(fl!("minimize")).on_press(Message::Minimize))
+                .push_maybe(terminal_btn);
+
+            let column = widget::column()
+                .spacing(8)
+                .align_items(Alignment::Center)
+                .push(ProgressBar::new(0.0..=100.0, progress))
+                .push_maybe(queued)
+                .push_maybe(self.batch_progress())
+                .push(buttons);
+
+            widget::container(widget::container(column).max_width(800))
+                .align_x(Horizontal::Center)
+                .into()
+        })
+    }
+
+    /// The minimal install UI: the packages opened, their install progress, and a way to
+    /// the full app.
+    fn compact_view(&self, state: ViewState) -> Element<Message> {
+        let names: Vec<&str> = self
+            .packages
+            .iter()
+            .map(|package| package.name.as_str())
+            .collect();
+
+        let status: Option<Element<'_, _>> = if state.closing {
+            Some(widget::text(fl!("closing-after-install")).into())
+        } else if let Some(progress) = self.progress {
+            Some(
+                widget::column()
+                    .spacing(8)
+                    .push(ProgressBar::new(0.0..=100.0, progress))
+                    .push_maybe(self.batch_progress())
+                    .into(),
             )
-            .align_x(Horizontal::Center)
+        } else {
+            self.parsing_indicator()
+        };
+
+        let install_btn: Option<Element<'_, _>> = matches!(
+            state.install_button,
+            InstallButton::Enabled | InstallButton::Disabled
+        )
+        .then(|| {
+            widget::button::suggested(fl!("install-file"))
+                .on_press_maybe(
+                    (state.install_button == InstallButton::Enabled)
+                        .then_some(Message::AskInstallation),
+                )
+                .into()
+        });
+
+        let buttons = widget::row()
+            .spacing(8)
+            .push(widget::button::standard(fl!("show-full-window")).on_press(Message::ExpandWindow))
+            .push_maybe(install_btn.filter(|_| !state.installing));
+
+        let content = widget::column()
+            .spacing(12)
+            .push(widget::text::heading(names.join(", ")))
+            .push_maybe(status)
+            .push(buttons);
+
+        widget::container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(16)
+            .align_y(Vertical::Center)
             .into()
-        })
     }
 }
 
+/// Writes the whole configuration and state through their handlers, logging failures.
+fn write_config(
+    config: &Config,
+    config_handler: Option<&cosmic_config::Config>,
+    state: &State,
+    state_handler: Option<&cosmic_config::Config>,
+) {
+    if let Some(handler) = config_handler {
+        if let Err(why) = config.write_entry(handler) {
+            eprintln!("failed to save config: {why}");
+        }
+    }
+    if let Some(handler) = state_handler {
+        if let Err(why) = state.write_entry(handler) {
+            eprintln!("failed to save state: {why}");
+        }
+    }
+}
+
+/// The embedded app icon, falling back to the installed icon theme when the embedded
+/// bytes aren't a usable SVG document.
+fn app_icon<'a>() -> Element<'a, Message> {
+    let is_svg = std::str::from_utf8(APP_ICON).is_ok_and(|svg| svg.contains("<svg"));
+    if is_svg {
+        return widget::svg(widget::svg::Handle::from_memory(APP_ICON)).into();
+    }
+
+    eprintln!("embedded app icon isn't an SVG document, using the icon theme");
+    widget::icon::from_name(AppModel::APP_ID).size(128).into()
+}
+
+/// Text style for errors, taken from the active theme so that high contrast themes apply.
+fn error_style() -> theme::Text {
+    theme::Text::Color(theme::active().cosmic().destructive_color().into())
+}
+
+/// Text style for warnings, taken from the active theme so that high contrast themes apply.
+fn warning_style() -> theme::Text {
+    theme::Text::Color(theme::active().cosmic().warning_color().into())
+}
+
+/// Caption naming the apt source a repository package comes from, as a warning when the
+/// source isn't one of the distribution's own archives.
+fn origin_label<'a>(origin: &Origin) -> Element<'a, Message> {
+    let suite = origin.suite.clone();
+    if origin.is_ppa() {
+        widget::text::caption(fl!("origin-ppa", suite = suite))
+            .style(warning_style())
+            .into()
+    } else if origin.is_third_party() {
+        widget::text::caption(fl!(
+            "origin-third-party",
+            suite = suite,
+            url = origin.url.clone()
+        ))
+        .style(warning_style())
+        .into()
+    } else {
+        widget::text::caption(fl!("origin", suite = suite)).into()
+    }
+}
+
+/// A blank bar standing in for a value that is still being read.
+fn skeleton_bar<'a>() -> Element<'a, Message> {
+    widget::container(widget::Space::new(Length::Fixed(64.0), Length::Fixed(10.0)))
+        .style(theme::Container::Card)
+        .into()
+}
+
+/// A link-styled button opening `url` in the default browser.
+fn link_button<'a>(url: String) -> Element<'a, Message> {
+    widget::button::link(url.clone())
+        .on_press(Message::OpenUrl(url))
+        .padding(0)
+        .into()
+}
+
+/// Lists dependency groups, each with a tooltip comparing the installed versions against
+/// the required ones.
+fn dependency_list<'a>(package: &Package, groups: &[Vec<Relation>]) -> Element<'a, Message> {
+    let mut column = widget::column().spacing(4);
+
+    for group in groups {
+        let icon = if package.is_satisfied(group) {
+            "emblem-ok-symbolic"
+        } else {
+            "dialog-warning-symbolic"
+        };
+
+        let label = widget::row()
+            .spacing(4)
+            .align_items(Alignment::Center)
+            .push(widget::icon::from_name(icon).size(16))
+            .push(widget::text(format_relations(&[group])));
+
+        let status = group
+            .iter()
+            .map(|relation| dependency_status(package, relation))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        column = column.push(widget::tooltip(
+            label,
+            widget::text(status),
+            widget::tooltip::Position::Top,
+        ));
+    }
+
+    column.into()
+}
+
+/// Describes whether the installed version of a dependency satisfies its constraint.
+fn dependency_status(package: &Package, relation: &Relation) -> String {
+    let name = relation.name.clone();
+    let required = relation
+        .constraint
+        .clone()
+        .unwrap_or_else(|| fl!("any-version"));
+
+    let installed = package.installed_dependencies.get(&relation.name);
+    match (installed, package.provider_of(relation)) {
+        (Some(installed), _) if relation.is_satisfied_by(installed) => fl!(
+            "dependency-satisfied",
+            name = name,
+            installed = installed.clone(),
+            required = required
+        ),
+        (_, Some(provider)) => fl!(
+            "dependency-provided",
+            name = name,
+            provider = provider.name.clone()
+        ),
+        (Some(installed), None) => fl!(
+            "dependency-unsatisfied",
+            name = name,
+            installed = installed.clone(),
+            required = required
+        ),
+        (None, None) => fl!("dependency-missing", name = name, required = required),
+    }
+}
+
+/// Runs `future` in the background, then sends the message `f` builds from its output,
+/// or nothing when the output is `None`, such as when the user cancelled a file chooser.
+fn app_command<T: Send + 'static>(
+    future: impl Future<Output = Option<T>> + Send + 'static,
+    f: impl FnOnce(T) -> Message + Send + 'static,
+) -> Command<Message> {
+    Command::perform(future, |output| match output {
+        Some(output) => cosmic::app::Message::App(f(output)),
+        None => cosmic::app::Message::None,
+    })
+}
+
+/// Runs a blocking install job in the background, forwarding its progress to the
+/// application and reporting its result with `on_finish`.
+///
+/// Progress updates closer together than `interval` are coalesced, keeping only the
+/// latest, except when the status changes, so that waiting states show up right away.
+fn install_subscription<I: Hash + 'static, T: Send + 'static>(
+    id: I,
+    interval: Duration,
+    job: impl FnOnce(Box<dyn FnMut(ItemProgress) + 'static>) -> T + Send + 'static,
+    on_finish: fn(T) -> Message,
+) -> Subscription<Message> {
+    subscription::channel(id, 16, move |msg_tx| async move {
+        let msg_tx = Arc::new(tokio::sync::Mutex::new(msg_tx));
+
+        let msg_tx1 = msg_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let send = move |message| {
+                let _ =
+                    futures::executor::block_on(async { msg_tx1.lock().await.send(message).await });
+            };
+
+            let coalesced: Rc<RefCell<Option<ItemProgress>>> = Rc::default();
+            let job_coalesced = coalesced.clone();
+            let job_send = send.clone();
+            let mut last: Option<(Instant, u32)> = None;
+
+            let result = job(Box::new(move |progress: ItemProgress| {
+                let due = !last.is_some_and(|(sent, status)| {
+                    sent.elapsed() < interval && status == progress.status
+                });
+                if due {
+                    last = Some((Instant::now(), progress.status));
+                    job_coalesced.borrow_mut().take();
+                    job_send(Message::Progress(progress));
+                } else {
+                    *job_coalesced.borrow_mut() = Some(progress);
+                }
+            }));
+
+            // Forward the last coalesced update, so the final state isn't lost.
+            if let Some(progress) = coalesced.borrow_mut().take() {
+                send(Message::Progress(progress));
+            }
+            send(on_finish(result));
+        })
+        .await
+        .unwrap();
+
+        pending().await
+    })
+}
+
+/// The dialog to display on top of the window.
+#[derive(Clone, Debug)]
+pub enum DialogPage {
+    /// What the application does, shown until the user dismisses it for good.
+    Welcome,
+    /// The pre-flight checks raised something the user must confirm before installing.
+    ConfirmInstall(Preflight),
+    /// What happened to each package of a finished install.
+    InstallSummary(Box<InstallSummary>),
+    /// The packages of an imported list, before installing them from the repositories.
+    InstallList(Box<ListResolution>),
+    /// A package script asks to accept a license before the install can continue.
+    License(Arc<debconf::Prompt>),
+}
+
 /// The context page to display in the context drawer.
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub enum ContextPage {
     #[default]
     About,
+    History,
+    Settings,
 }
 
 impl ContextPage {
     fn title(&self) -> String {
         match self {
             Self::About => fl!("about"),
+            Self::History => fl!("history"),
+            Self::Settings => fl!("settings"),
         }
     }
 }
@@ -495,6 +3336,8 @@ impl ContextPage {
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum MenuAction {
     About,
+    History,
+    Settings,
 }
 
 impl menu::action::MenuAction for MenuAction {
@@ -503,6 +3346,152 @@ impl menu::action::MenuAction for MenuAction {
     fn message(&self) -> Self::Message {
         match self {
             MenuAction::About => Message::ToggleContextPage(ContextPage::About),
+            MenuAction::History => Message::ToggleContextPage(ContextPage::History),
+            MenuAction::Settings => Message::ToggleContextPage(ContextPage::Settings),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn view_state_empty() {
+        let state = ViewState::new(ViewInputs::default());
+        assert_eq!(state.install_button, InstallButton::Hidden);
+        assert!(!state.files && !state.details && !state.installing);
+    }
+
+    #[test]
+    fn view_state_selected() {
+        let preflight = Preflight::passing();
+        let state = ViewState::new(ViewInputs {
+            installable: true,
+            preflight: Some(&preflight),
+            files: true,
+            details: true,
+            ..ViewInputs::default()
+        });
+        assert_eq!(state.install_button, InstallButton::Enabled);
+        assert!(state.files && state.details);
+    }
+
+    #[test]
+    fn view_state_checking() {
+        let state = ViewState::new(ViewInputs {
+            installable: true,
+            files: true,
+            ..ViewInputs::default()
+        });
+        assert_eq!(state.install_button, InstallButton::Disabled);
+    }
+
+    #[test]
+    fn view_state_installing() {
+        let preflight = Preflight::passing();
+        let state = ViewState::new(ViewInputs {
+            installable: true,
+            install_busy: true,
+            preflight: Some(&preflight),
+            installing: true,
+            files: true,
+            ..ViewInputs::default()
+        });
+        assert!(state.installing);
+        assert_eq!(state.install_button, InstallButton::Disabled);
+    }
+
+    #[test]
+    fn view_state_installed() {
+        let state = ViewState::new(ViewInputs {
+            succeeded: true,
+            files: true,
+            closing: true,
+            ..ViewInputs::default()
+        });
+        assert_eq!(state.install_button, InstallButton::Succeeded);
+        assert!(state.closing);
+    }
+
+    #[test]
+    fn view_state_error() {
+        let preflight = Preflight {
+            daemon_available: false,
+            ..Preflight::passing()
+        };
+        let state = ViewState::new(ViewInputs {
+            installable: true,
+            preflight: Some(&preflight),
+            files: true,
+            ..ViewInputs::default()
+        });
+        assert_eq!(state.install_button, InstallButton::Disabled);
+    }
+
+    /// A model whose configuration is stored under `dir`, leaving the user's alone.
+    fn model(dir: &Path) -> AppModel {
+        let handler = cosmic_config::Config::with_custom_path(
+            AppModel::APP_ID,
+            Config::VERSION,
+            dir.to_path_buf(),
+        )
+        .unwrap();
+        AppModel::new(Core::default(), Some(handler), None, false)
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("wizard-{name}-{}", std::process::id()))
+    }
+
+    #[test]
+    fn config_flushed_on_exit_persists() {
+        let dir = temp_dir("quit");
+        let mut app = model(&dir);
+
+        let _ = app.update(Message::SetLargePackageThreshold(0));
+        // A change whose write was lost, as when a stale watcher update overwrote it.
+        app.config.stop_on_error = !app.config.stop_on_error;
+        let expected = app.config.clone();
+        let _ = app.update(Message::Quit);
+
+        let persisted = model(&dir).config;
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(persisted, expected);
+    }
+
+    #[test]
+    fn repeated_install_presses_start_one_install() {
+        let dir = temp_dir("install");
+        let mut app = model(&dir);
+        app.dialog = None;
+
+        // The second press arrives before the checks started by the first finish.
+        let started: usize = (0..2)
+            .map(|_| app.update(Message::AskInstallation).actions().len())
+            .sum();
+        assert_eq!(started, 1);
+
+        let _ = app.update(Message::PreflightChecked(Box::new(Preflight::passing())));
+        assert!(matches!(app.dialog, Some(DialogPage::ConfirmInstall(_))));
+        assert!(app.update(Message::AskInstallation).actions().is_empty());
+        assert!(!app.install_running());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn install_press_ignored_while_busy() {
+        let mut request = InstallRequest::default();
+        assert!(!request.press(true, false));
+        assert!(!request.press(false, true));
+        assert!(!request.is_pending());
+    }
+
+    #[test]
+    fn install_press_accepted_after_settling() {
+        let mut request = InstallRequest::default();
+        assert!(request.press(false, false));
+        request.settle();
+        assert!(request.press(false, false));
+    }
+}
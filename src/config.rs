@@ -1,9 +1,109 @@
 // SPDX-License-Identifier: {{LICENSE}}
 
+use std::path::{Path, PathBuf};
+
 use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
 
-#[derive(Debug, Default, Clone, CosmicConfigEntry, Eq, PartialEq)]
+use crate::file_dialog::FileDialog;
+use crate::history::HistoryEntry;
+
+#[derive(Debug, Clone, CosmicConfigEntry, Eq, PartialEq)]
 #[version = 1]
 pub struct Config {
     demo: String,
+    /// Whether the user dismissed the welcome dialog shown on first launch.
+    pub welcome_dismissed: bool,
+    /// Offer to follow the dpkg log in a terminal while installing.
+    pub show_terminal_log: bool,
+    /// Installed size, in megabytes, above which installs ask for confirmation.
+    pub large_package_threshold_mb: u64,
+    /// Show a tray icon with the install progress while the window is minimized.
+    pub minimize_to_tray: bool,
+    /// Skip the rest of a batch install after a package fails, instead of continuing.
+    pub stop_on_error: bool,
+    /// Close the window once every package installed successfully.
+    pub close_after_install: bool,
+    /// When launched with a file, show only a small progress window that closes itself
+    /// once the install succeeded.
+    pub minimal_install_ui: bool,
+    /// Clear the selected files once they installed successfully, ready for the next one.
+    pub reset_after_install: bool,
+    /// Root directory to install into, empty for the system root.
+    pub install_root: String,
+    /// Minimum time between progress updates, in milliseconds, 0 for every update.
+    pub progress_interval_ms: u64,
+    /// Focus the confirm button of confirmation dialogs instead of Cancel.
+    pub focus_confirm: bool,
+    /// Which file dialog selects packages.
+    pub file_dialog: FileDialog,
+    /// Show options that can break the system when misused.
+    pub advanced_mode: bool,
+    /// Install with `dpkg --force-depends`, only honored in advanced mode.
+    pub ignore_dependencies: bool,
+    /// Shell command to run after a successful install, only honored in advanced mode.
+    pub post_install_command: String,
+    /// Run [`Config::post_install_command`] as root through pkexec.
+    pub post_install_as_root: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            demo: String::new(),
+            welcome_dismissed: false,
+            show_terminal_log: false,
+            large_package_threshold_mb: 1000,
+            minimize_to_tray: false,
+            stop_on_error: false,
+            close_after_install: false,
+            minimal_install_ui: false,
+            reset_after_install: false,
+            install_root: String::new(),
+            progress_interval_ms: 250,
+            focus_confirm: false,
+            file_dialog: FileDialog::Auto,
+            advanced_mode: false,
+            ignore_dependencies: false,
+            post_install_command: String::new(),
+            post_install_as_root: false,
+        }
+    }
+}
+
+impl Config {
+    /// The alternative install root, or `None` when installing into the system root.
+    pub fn install_root(&self) -> Option<PathBuf> {
+        let root = self.install_root.trim();
+        (!root.is_empty() && Path::new(root) != Path::new("/")).then(|| PathBuf::from(root))
+    }
+
+    /// Whether the configured install root isn't an existing directory, such as after it
+    /// was removed since it was set.
+    pub fn install_root_missing(&self) -> bool {
+        self.install_root().is_some_and(|root| !root.is_dir())
+    }
+
+    /// Whether installs should ignore unsatisfied dependencies.
+    pub fn ignore_dependencies(&self) -> bool {
+        self.advanced_mode && self.ignore_dependencies
+    }
+
+    /// The command to run after a successful install, if one is configured.
+    pub fn post_install_command(&self) -> Option<&str> {
+        let command = self.post_install_command.trim();
+        (self.advanced_mode && !command.is_empty()).then_some(command)
+    }
+}
+
+/// Choices offered for [`Config::large_package_threshold_mb`].
+pub const LARGE_PACKAGE_THRESHOLDS_MB: [u64; 6] = [250, 500, 1000, 2000, 5000, 10000];
+
+/// Choices offered for [`Config::progress_interval_ms`].
+pub const PROGRESS_INTERVALS_MS: [u64; 5] = [0, 100, 250, 500, 1000];
+
+/// Application state that persists between runs but isn't a user preference.
+#[derive(Debug, Default, Clone, CosmicConfigEntry, Eq, PartialEq)]
+#[version = 1]
+pub struct State {
+    pub history: Vec<HistoryEntry>,
 }
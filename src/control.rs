@@ -0,0 +1,171 @@
+//! Parsing of deb822 control data, as found in `.dsc` files and `.deb` control members.
+
+use std::fmt;
+
+use crate::version;
+
+/// A single paragraph of `Key: value` fields.
+#[derive(Debug, Clone, Default)]
+pub struct Paragraph {
+    fields: Vec<(String, String)>,
+}
+
+impl Paragraph {
+    /// Parses the first paragraph of `text`, skipping any PGP clearsign armor around it.
+    pub fn parse(text: &str) -> Self {
+        let mut fields: Vec<(String, String)> = Vec::new();
+        let mut lines = text.lines();
+
+        while let Some(line) = lines.next() {
+            if line.starts_with("-----BEGIN PGP SIGNED MESSAGE-----") {
+                // Skip the armor headers, which end at the first empty line.
+                for header in lines.by_ref() {
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                }
+                continue;
+            }
+
+            if line.starts_with("-----BEGIN PGP SIGNATURE-----") {
+                break;
+            }
+
+            if line.trim().is_empty() {
+                if fields.is_empty() {
+                    continue;
+                }
+                break;
+            }
+
+            if line.starts_with([' ', '\t']) {
+                if let Some((_, value)) = fields.last_mut() {
+                    let continuation = line.trim();
+                    value.push('\n');
+                    if continuation != "." {
+                        value.push_str(continuation);
+                    }
+                }
+                continue;
+            }
+
+            if let Some((key, value)) = line.split_once(':') {
+                fields.push((key.trim().to_string(), value.trim().to_string()));
+            }
+        }
+
+        Self { fields }
+    }
+
+    /// Returns the value of a field, matching its name case-insensitively.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Every field, in the order they appear.
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+/// A single package relation, such as `libc6 (>= 2.34)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relation {
+    pub name: String,
+    pub constraint: Option<String>,
+}
+
+impl Relation {
+    /// Whether `version` satisfies the version constraint of the relation, if it has one.
+    pub fn is_satisfied_by(&self, version: &str) -> bool {
+        let Some(constraint) = &self.constraint else {
+            return true;
+        };
+
+        let split = constraint
+            .find(|c: char| !matches!(c, '<' | '>' | '='))
+            .unwrap_or(constraint.len());
+        let (operator, required) = constraint.split_at(split);
+        let ordering = version::compare(version, required.trim());
+
+        match operator {
+            // `<` and `>` are obsolete spellings of `<=` and `>=`.
+            ">=" | ">" => ordering.is_ge(),
+            "<=" | "<" => ordering.is_le(),
+            ">>" => ordering.is_gt(),
+            "<<" => ordering.is_lt(),
+            "=" => ordering.is_eq(),
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.constraint {
+            Some(constraint) => write!(f, "{} ({constraint})", self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// Parses a relationship field into groups of alternatives.
+///
+/// Every group must be satisfied, and a group is satisfied by any one of its alternatives.
+/// Architecture restrictions (`[amd64]`) and build profiles (`<!nocheck>`) are ignored.
+pub fn parse_relations(value: &str) -> Vec<Vec<Relation>> {
+    value
+        .split(',')
+        .map(|group| {
+            group
+                .split('|')
+                .filter_map(parse_relation)
+                .collect::<Vec<_>>()
+        })
+        .filter(|group| !group.is_empty())
+        .collect()
+}
+
+fn parse_relation(text: &str) -> Option<Relation> {
+    let text = text.trim();
+    let end = text
+        .find(|c: char| c.is_whitespace() || matches!(c, '(' | '[' | '<'))
+        .unwrap_or(text.len());
+
+    // Drop architecture qualifiers such as `:any` or `:native`.
+    let name = text[..end].split(':').next().unwrap_or_default();
+    if name.is_empty() {
+        return None;
+    }
+
+    let constraint = text[end..].split_once('(').and_then(|(_, rest)| {
+        rest.split_once(')')
+            .map(|(constraint, _)| constraint.split_whitespace().collect::<Vec<_>>().join(" "))
+    });
+
+    Some(Relation {
+        name: name.to_string(),
+        constraint,
+    })
+}
+
+/// Formats parsed relations back into a human readable, comma separated list.
+pub fn format_relations<G: AsRef<[Relation]>>(relations: &[G]) -> String {
+    relations
+        .iter()
+        .map(|group| {
+            group
+                .as_ref()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" | ")
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,421 @@
+//! Reading metadata directly from `.deb` archives.
+
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use flate2::read::GzDecoder;
+use xz2::read::XzDecoder;
+use xz2::stream::Stream;
+
+use crate::control::Paragraph;
+
+/// Every `.deb` is an `ar` archive, which starts with this magic.
+const AR_MAGIC: &[u8] = b"!<arch>\n";
+
+/// Compressions dpkg accepts for the data archive that can't be read here, so members
+/// using them are only checked to be present and left for dpkg to verify.
+const UNREADABLE_COMPRESSIONS: &[&str] = &[".bz2"];
+
+/// Why metadata couldn't be read from a `.deb` archive.
+#[derive(Debug)]
+pub enum PackageParseError {
+    /// The file couldn't be opened or read.
+    Io(io::Error),
+    /// The file isn't a well-formed `ar` archive, or lacks the members every package has.
+    BadArchive(String),
+    /// The control archive lacks a required member, such as `control`.
+    MissingMember(String),
+    /// A compressed member couldn't be decompressed, or uses an unsupported compression.
+    Decompression(String),
+    /// The `control` file lacks required fields.
+    MalformedControl(String),
+}
+
+impl PackageParseError {
+    /// Whether the file itself is broken, rather than unreadable.
+    pub fn is_corrupt(&self) -> bool {
+        !matches!(self, Self::Io(_))
+    }
+}
+
+impl fmt::Display for PackageParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(why) => write!(f, "failed to read the package: {why}"),
+            Self::BadArchive(reason) => write!(f, "not a valid Debian package: {reason}"),
+            Self::MissingMember(member) => write!(f, "the package has no {member} file"),
+            Self::Decompression(reason) => {
+                write!(f, "failed to decompress the package: {reason}")
+            }
+            Self::MalformedControl(reason) => {
+                write!(f, "the package's control file is malformed: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PackageParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(why) => Some(why),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PackageParseError {
+    fn from(why: io::Error) -> Self {
+        Self::Io(why)
+    }
+}
+
+fn bad_archive(reason: impl fmt::Display) -> PackageParseError {
+    PackageParseError::BadArchive(reason.to_string())
+}
+
+fn decompression(reason: impl fmt::Display) -> PackageParseError {
+    PackageParseError::Decompression(reason.to_string())
+}
+
+/// Checks that a file is a complete `.deb` archive, reading every member so that
+/// truncated or corrupt archives are caught before any install is attempted.
+///
+/// Failures other than [`PackageParseError::Io`] mean the file is corrupt.
+pub fn validate(path: &str) -> Result<(), PackageParseError> {
+    let mut magic = [0; AR_MAGIC.len()];
+    File::open(path)?
+        .read_exact(&mut magic)
+        .map_err(|_| bad_archive("file is too short"))?;
+    if magic != AR_MAGIC {
+        return Err(bad_archive("missing ar archive magic"));
+    }
+
+    let mut archive = ar::Archive::new(File::open(path)?);
+    let (mut version, mut control, mut data) = (false, false, false);
+
+    while let Some(entry) = archive.next_entry() {
+        let mut entry = entry.map_err(bad_archive)?;
+        let identifier = String::from_utf8_lossy(entry.header().identifier()).into_owned();
+
+        if identifier == "debian-binary" {
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents).map_err(bad_archive)?;
+            version = contents.starts_with("2.");
+        } else if let Some(extension) = identifier.strip_prefix("control.tar") {
+            read_tar(extension, entry)?;
+            control = true;
+        } else if let Some(extension) = identifier.strip_prefix("data.tar") {
+            if UNREADABLE_COMPRESSIONS.contains(&extension) {
+                eprintln!("can't verify {identifier} of {path}, leaving it to dpkg");
+            } else {
+                read_tar(extension, entry)?;
+            }
+            data = true;
+        }
+    }
+
+    match (version, control, data) {
+        (false, _, _) => Err(bad_archive("missing or unsupported debian-binary member")),
+        (_, false, _) => Err(bad_archive("missing control archive")),
+        (_, _, false) => Err(bad_archive("missing data archive")),
+        _ => Ok(()),
+    }
+}
+
+/// Reads every entry of a compressed tar member to the end.
+fn read_tar(extension: &str, reader: impl Read) -> Result<(), PackageParseError> {
+    let mut archive = tar::Archive::new(decompress(extension, reader)?);
+    for entry in archive.entries().map_err(decompression)? {
+        io::copy(&mut entry.map_err(decompression)?, &mut io::sink()).map_err(decompression)?;
+    }
+
+    Ok(())
+}
+
+/// Estimates the installed size of a package, in bytes, by summing the sizes of the files
+/// in its data archive. Only the tar headers are parsed, nothing is extracted.
+pub fn data_size(path: &str) -> Result<u64, PackageParseError> {
+    let mut archive = ar::Archive::new(File::open(path)?);
+
+    while let Some(entry) = archive.next_entry() {
+        let entry = entry.map_err(bad_archive)?;
+        let identifier = String::from_utf8_lossy(entry.header().identifier()).into_owned();
+        let Some(extension) = identifier.strip_prefix("data.tar") else {
+            continue;
+        };
+
+        let mut data = tar::Archive::new(decompress(extension, entry)?);
+        let mut size = 0;
+        for file in data.entries().map_err(decompression)? {
+            let file = file.map_err(decompression)?;
+            if file.header().entry_type().is_file() {
+                size += file.header().size().map_err(bad_archive)?;
+            }
+        }
+
+        return Ok(size);
+    }
+
+    Err(bad_archive("missing data archive"))
+}
+
+/// The file [`buffer_stdin`] buffered standard input into, until [`remove_buffered_stdin`].
+static BUFFERED_STDIN: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Buffers a package piped on standard input into a temporary file, so it can be read
+/// and installed like any other file. The file is removed by [`remove_buffered_stdin`].
+///
+/// The file is created exclusively and only readable by the user, preferably in the
+/// user's runtime directory, so other users can't swap its contents before the install.
+pub fn buffer_stdin() -> anyhow::Result<PathBuf> {
+    let mut contents = Vec::new();
+    io::stdin().lock().read_to_end(&mut contents)?;
+
+    if !contents.starts_with(AR_MAGIC) {
+        return Err(anyhow::anyhow!("standard input isn't a .deb package"));
+    }
+
+    let dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_dir())
+        .unwrap_or_else(std::env::temp_dir);
+    let (path, mut file) = create_exclusive(&dir)?;
+    *BUFFERED_STDIN.lock().unwrap() = Some(path.clone());
+    file.write_all(&contents)?;
+
+    Ok(path)
+}
+
+/// Creates a new file in `dir` that didn't exist before, never following symlinks.
+fn create_exclusive(dir: &Path) -> io::Result<(PathBuf, File)> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.subsec_nanos());
+
+    let mut attempt = 0;
+    loop {
+        let name = format!("wizard-stdin-{}-{nanos}-{attempt}.deb", std::process::id());
+        let path = dir.join(name);
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&path)
+        {
+            Ok(file) => return Ok((path, file)),
+            Err(why) if why.kind() == io::ErrorKind::AlreadyExists && attempt < 100 => {
+                attempt += 1;
+            }
+            Err(why) => return Err(why),
+        }
+    }
+}
+
+/// Removes the file standard input was buffered into, if any.
+pub fn remove_buffered_stdin() {
+    let Some(path) = BUFFERED_STDIN.lock().unwrap().take() else {
+        return;
+    };
+    if let Err(why) = fs::remove_file(&path) {
+        eprintln!("failed to remove {}: {why}", path.display());
+    }
+}
+
+/// Lists the `.deb` files directly inside `dir`, sorted by name.
+pub fn packages_in_dir(dir: &Path) -> io::Result<Vec<String>> {
+    let mut paths: Vec<String> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "deb"))
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// Reads a member of the control archive, such as `control` or `conffiles`.
+///
+/// Returns `None` if the control archive doesn't contain the member.
+pub fn read_control_member(path: &str, member: &str) -> Result<Option<String>, PackageParseError> {
+    let mut archive = ar::Archive::new(File::open(path)?);
+
+    while let Some(entry) = archive.next_entry() {
+        let entry = entry.map_err(bad_archive)?;
+        let identifier = String::from_utf8_lossy(entry.header().identifier()).into_owned();
+        let Some(extension) = identifier.strip_prefix("control.tar") else {
+            continue;
+        };
+
+        let mut control = tar::Archive::new(decompress(extension, entry)?);
+        for file in control.entries().map_err(decompression)? {
+            let mut file = file.map_err(decompression)?;
+            let name = file.path().map_err(bad_archive)?;
+            if name.to_string_lossy().trim_start_matches("./") == member {
+                let mut contents = String::new();
+                file.read_to_string(&mut contents).map_err(decompression)?;
+                return Ok(Some(contents));
+            }
+        }
+
+        return Ok(None);
+    }
+
+    Err(bad_archive("missing control archive"))
+}
+
+/// Reads the `control` file of a package, which must at least name the package.
+pub fn read_control(path: &str) -> Result<Paragraph, PackageParseError> {
+    let control = read_control_member(path, "control")?
+        .ok_or_else(|| PackageParseError::MissingMember("control".to_string()))?;
+
+    let paragraph = Paragraph::parse(&control);
+    for field in ["Package", "Version"] {
+        if paragraph.get(field).is_none() {
+            return Err(PackageParseError::MalformedControl(format!(
+                "missing {field} field"
+            )));
+        }
+    }
+
+    Ok(paragraph)
+}
+
+/// Lists the configuration files a package ships, which dpkg preserves on upgrade.
+///
+/// Packages without a `conffiles` member don't ship any.
+pub fn read_conffiles(path: &str) -> Result<Vec<String>, PackageParseError> {
+    let conffiles = read_control_member(path, "conffiles")?.unwrap_or_default();
+
+    // Lines may start with flags such as `remove-on-upgrade`, the path always comes last.
+    Ok(conffiles
+        .lines()
+        .filter_map(|line| line.split_whitespace().last())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Reads the names of the dpkg triggers the package activates, such as `ldconfig`.
+///
+/// Packages without a `triggers` member don't activate any explicitly, and the triggers
+/// the package is interested in itself are left out.
+pub fn read_triggers(path: &str) -> Result<Vec<String>, PackageParseError> {
+    let triggers = read_control_member(path, "triggers")?.unwrap_or_default();
+
+    Ok(triggers
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split('#').next()?.split_whitespace();
+            // `activate`, `activate-await` and `activate-noawait` all name one trigger.
+            words.next()?.starts_with("activate").then_some(())?;
+            words.next()
+        })
+        .map(str::to_string)
+        .collect())
+}
+
+/// Wraps an archive member in the decompressor matching its file extension.
+fn decompress<'a>(
+    extension: &str,
+    reader: impl Read + 'a,
+) -> Result<Box<dyn Read + 'a>, PackageParseError> {
+    Ok(match extension {
+        "" => Box::new(reader),
+        ".gz" => Box::new(GzDecoder::new(reader)),
+        ".xz" => Box::new(XzDecoder::new(reader)),
+        ".lzma" => {
+            let stream = Stream::new_lzma_decoder(u64::MAX).map_err(decompression)?;
+            Box::new(XzDecoder::new_stream(reader, stream))
+        }
+        ".zst" => Box::new(zstd::stream::read::Decoder::new(reader).map_err(decompression)?),
+        other => return Err(decompression(format!("unsupported compression {other}"))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"))
+    }
+
+    #[test]
+    fn validates_complete_package() {
+        validate(&fixture("valid.deb")).unwrap();
+    }
+
+    #[test]
+    fn truncated_package_is_corrupt() {
+        let error = validate(&fixture("truncated.deb")).unwrap_err();
+        assert!(error.is_corrupt(), "{error}");
+    }
+
+    #[test]
+    fn validates_lzma_data() {
+        validate(&fixture("data-lzma.deb")).unwrap();
+        assert_eq!(data_size(&fixture("data-lzma.deb")).unwrap(), 128);
+    }
+
+    #[test]
+    fn validates_bzip2_data() {
+        validate(&fixture("data-bz2.deb")).unwrap();
+    }
+
+    #[test]
+    fn bad_magic_is_bad_archive() {
+        let error = validate(&fixture("bad-magic.deb")).unwrap_err();
+        assert!(matches!(error, PackageParseError::BadArchive(_)), "{error}");
+    }
+
+    #[test]
+    fn missing_control_archive_is_bad_archive() {
+        let error = read_control(&fixture("no-control.deb")).unwrap_err();
+        assert!(matches!(error, PackageParseError::BadArchive(_)), "{error}");
+    }
+
+    #[test]
+    fn missing_control_file_is_missing_member() {
+        let error = read_control(&fixture("no-control-file.deb")).unwrap_err();
+        assert!(
+            matches!(&error, PackageParseError::MissingMember(member) if member == "control"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn unsupported_compression_is_decompression() {
+        let error = read_control(&fixture("unsupported-compression.deb")).unwrap_err();
+        assert!(
+            matches!(error, PackageParseError::Decompression(_)),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn missing_version_is_malformed_control() {
+        let error = read_control(&fixture("missing-version.deb")).unwrap_err();
+        assert!(
+            matches!(error, PackageParseError::MalformedControl(_)),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn missing_file_is_io() {
+        let error = read_control(&fixture("nonexistent.deb")).unwrap_err();
+        assert!(matches!(error, PackageParseError::Io(_)), "{error}");
+        assert!(!error.is_corrupt());
+    }
+
+    #[test]
+    fn reads_control_fields() {
+        let control = read_control(&fixture("valid.deb")).unwrap();
+        assert_eq!(control.get("Package"), Some("fixture"));
+        assert_eq!(control.get("Version"), Some("1.0"));
+    }
+}
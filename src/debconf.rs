@@ -0,0 +1,182 @@
+//! A minimal debconf frontend, so that license agreements shown by package scripts reach
+//! the user instead of being declined by the noninteractive frontend.
+//!
+//! PackageKit connects the debconf of package scripts to the socket passed in the
+//! `frontend-socket` hint, and speaks debconf's passthrough protocol on it. Only license
+//! questions are asked, every other question keeps its default answer.
+
+use std::collections::HashMap;
+use std::env;
+use std::future::Future;
+use std::path::PathBuf;
+use std::process;
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::oneshot;
+
+/// Where the frontend listens, in the user's runtime directory.
+pub fn socket_path() -> PathBuf {
+    env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(env::temp_dir)
+        .join(format!("wizard-debconf-{}.socket", process::id()))
+}
+
+/// A debconf question, as described by the package's templates.
+#[derive(Debug, Clone, Default)]
+pub struct Question {
+    pub tag: String,
+    /// The template type, such as `boolean` or `note`.
+    pub kind: String,
+    pub description: String,
+    pub extended_description: String,
+}
+
+impl Question {
+    /// Whether the question presents or asks to accept a license, going by its name,
+    /// such as `msttcorefonts/accepted-mscorefonts-eula`.
+    fn is_license(&self) -> bool {
+        let tag = self.tag.to_lowercase();
+        ["licen", "eula"].iter().any(|word| tag.contains(word))
+    }
+}
+
+/// License questions a package script is waiting on, with the channel the user's answer
+/// is sent back on.
+#[derive(Debug)]
+pub struct Prompt {
+    pub questions: Vec<Question>,
+    reply: Mutex<Option<oneshot::Sender<bool>>>,
+}
+
+impl Prompt {
+    /// Accepts or declines the license, letting the package script continue.
+    pub fn answer(&self, accepted: bool) {
+        if let Some(reply) = self.reply.lock().unwrap().take() {
+            let _ = reply.send(accepted);
+        }
+    }
+}
+
+/// Binds the frontend's socket, replacing one left over by an earlier run.
+///
+/// Binding doesn't need the async runtime, so the socket can exist before the transaction
+/// that connects to it starts, and connections wait until [`serve`] accepts them.
+pub fn bind() -> anyhow::Result<std::os::unix::net::UnixListener> {
+    let path = socket_path();
+    let _ = std::fs::remove_file(&path);
+    let listener = std::os::unix::net::UnixListener::bind(path)?;
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+/// Answers debconf sessions on `listener` one at a time, passing license questions to
+/// `ask` and waiting for the user to answer them.
+pub async fn serve<F, Fut>(listener: std::os::unix::net::UnixListener, mut ask: F)
+where
+    F: FnMut(Arc<Prompt>) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let listener = match UnixListener::from_std(listener) {
+        Ok(listener) => listener,
+        Err(why) => {
+            eprintln!("failed to listen for debconf connections: {why}");
+            return;
+        }
+    };
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                if let Err(why) = session(stream, &mut ask).await {
+                    eprintln!("debconf session failed: {why}");
+                }
+            }
+            Err(why) => {
+                eprintln!("failed to accept debconf connection: {why}");
+                return;
+            }
+        }
+    }
+}
+
+async fn session<F, Fut>(stream: UnixStream, ask: &mut F) -> anyhow::Result<()>
+where
+    F: FnMut(Arc<Prompt>) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let mut questions: HashMap<String, Question> = HashMap::new();
+    let mut values: HashMap<String, String> = HashMap::new();
+    let mut pending: Vec<String> = Vec::new();
+
+    while let Some(line) = lines.next_line().await? {
+        let (command, rest) = line.split_once(' ').unwrap_or((&line, ""));
+
+        let reply = match command {
+            "DATA" => {
+                let mut parts = rest.splitn(3, ' ');
+                let (tag, item) = (parts.next().unwrap_or_default(), parts.next());
+                // The passthrough frontend escapes newlines in the value.
+                let value = parts.next().unwrap_or_default().replace("\\n", "\n");
+                let question = questions
+                    .entry(tag.to_string())
+                    .or_insert_with(|| Question {
+                        tag: tag.to_string(),
+                        ..Question::default()
+                    });
+                match item {
+                    Some("type") => question.kind = value,
+                    Some("description") => question.description = value,
+                    Some("extended_description") => question.extended_description = value,
+                    _ => {}
+                }
+                String::from("0")
+            }
+            "SET" => {
+                let (tag, value) = rest.split_once(' ').unwrap_or((rest, ""));
+                values.insert(tag.to_string(), value.to_string());
+                String::from("0")
+            }
+            "GET" => format!("0 {}", values.get(rest).map_or("", String::as_str)),
+            "INPUT" => {
+                if let Some((_priority, tag)) = rest.split_once(' ') {
+                    pending.push(tag.to_string());
+                }
+                String::from("0")
+            }
+            "GO" => {
+                let shown: Vec<Question> = pending
+                    .drain(..)
+                    .filter_map(|tag| questions.get(&tag).cloned())
+                    .filter(|question| matches!(question.kind.as_str(), "boolean" | "note"))
+                    .collect();
+
+                if shown.iter().any(Question::is_license) {
+                    let (reply, answer) = oneshot::channel();
+                    let prompt = Prompt {
+                        questions: shown.clone(),
+                        reply: Mutex::new(Some(reply)),
+                    };
+                    ask(Arc::new(prompt)).await;
+                    let accepted = answer.await.unwrap_or(false);
+
+                    for question in shown.iter().filter(|question| question.kind == "boolean") {
+                        values.insert(question.tag.clone(), accepted.to_string());
+                    }
+                }
+                String::from("0")
+            }
+            "STOP" => break,
+            // Capabilities, titles and everything else are acknowledged and ignored.
+            _ => String::from("0"),
+        };
+
+        writer.write_all(format!("{reply}\n").as_bytes()).await?;
+    }
+
+    Ok(())
+}
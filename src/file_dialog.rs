@@ -0,0 +1,194 @@
+//! Asks the user for files, through the XDG desktop portal or a native dialog.
+
+use std::future::Future;
+
+use ashpd::desktop::file_chooser::{FileFilter, SelectedFiles};
+use ashpd::url::Url;
+use serde::{Deserialize, Serialize};
+
+/// Which file dialog is shown to select packages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FileDialog {
+    /// The portal, or the native dialog when no portal answers.
+    #[default]
+    Auto,
+    Portal,
+    /// The GTK dialog, for setups without a file chooser portal.
+    Native,
+}
+
+impl FileDialog {
+    /// Every choice, in the order the settings offer them.
+    pub const ALL: [Self; 3] = [Self::Auto, Self::Portal, Self::Native];
+}
+
+/// Asks the user for package files to install, returning `None` when they cancelled.
+pub async fn pick_packages(dialog: FileDialog) -> Option<Vec<String>> {
+    show(dialog, portal_packages, native_packages).await
+}
+
+/// Asks the user for a JSON file to open, returning `None` when they cancelled.
+pub async fn open_json(dialog: FileDialog, title: &str, accept_label: &str) -> Option<String> {
+    show(
+        dialog,
+        move || async move {
+            let request = SelectedFiles::open_file()
+                .title(title)
+                .accept_label(accept_label)
+                .modal(true)
+                .filter(json_filter())
+                .send()
+                .await?;
+            Ok::<_, ashpd::Error>(first_path(request.response().ok()))
+        },
+        move || async move {
+            let file = rfd::AsyncFileDialog::new()
+                .set_title(title)
+                .add_filter("JSON", &["json"])
+                .pick_file()
+                .await?;
+            Some(file.path().to_string_lossy().into_owned())
+        },
+    )
+    .await
+}
+
+/// Asks the user where to save a JSON file, suggesting `file_name`, returning `None`
+/// when they cancelled.
+pub async fn save_json(
+    dialog: FileDialog,
+    title: &str,
+    accept_label: &str,
+    file_name: &str,
+) -> Option<String> {
+    show(
+        dialog,
+        move || async move {
+            let request = SelectedFiles::save_file()
+                .title(title)
+                .accept_label(accept_label)
+                .current_name(file_name)
+                .modal(true)
+                .filter(json_filter())
+                .send()
+                .await?;
+            Ok::<_, ashpd::Error>(first_path(request.response().ok()))
+        },
+        move || async move {
+            let file = rfd::AsyncFileDialog::new()
+                .set_title(title)
+                .set_file_name(file_name)
+                .add_filter("JSON", &["json"])
+                .save_file()
+                .await?;
+            Some(file.path().to_string_lossy().into_owned())
+        },
+    )
+    .await
+}
+
+/// Shows the `portal` or `native` dialog as `dialog` asks, falling back to the native
+/// one in [`FileDialog::Auto`] when no portal answers.
+async fn show<T, P, N>(
+    dialog: FileDialog,
+    portal: impl FnOnce() -> P,
+    native: impl FnOnce() -> N,
+) -> Option<T>
+where
+    P: Future<Output = ashpd::Result<Option<T>>>,
+    N: Future<Output = Option<T>>,
+{
+    match dialog {
+        FileDialog::Portal => portal().await.unwrap_or_else(|why| {
+            eprintln!("failed to open the file chooser portal: {why}");
+            None
+        }),
+        FileDialog::Native => native().await,
+        FileDialog::Auto => match portal().await {
+            Ok(selected) => selected,
+            Err(why) => {
+                eprintln!("file chooser portal isn't available, using a native dialog: {why}");
+                native().await
+            }
+        },
+    }
+}
+
+fn json_filter() -> FileFilter {
+    FileFilter::new("*.json").mimetype("application/json")
+}
+
+/// The first local file of a portal response, `None` if the user cancelled.
+fn first_path(files: Option<SelectedFiles>) -> Option<String> {
+    selected_paths(files?.uris()).into_iter().next()
+}
+
+/// Asks through the portal, which fails when no portal implements the file chooser.
+async fn portal_packages() -> ashpd::Result<Option<Vec<String>>> {
+    let request = SelectedFiles::open_file()
+        .title("Select Packages to install")
+        .accept_label("Add")
+        .multiple(true)
+        .modal(true)
+        .filter(FileFilter::new("*.deb").mimetype("application/vnd.debian.binary-package"))
+        .filter(FileFilter::new("*.dsc").mimetype("text/x-dsc"))
+        .send()
+        .await?;
+
+    // Once the request was sent, an error response means the user cancelled it.
+    Ok(request
+        .response()
+        .ok()
+        .map(|files| selected_paths(files.uris())))
+}
+
+async fn native_packages() -> Option<Vec<String>> {
+    let files = rfd::AsyncFileDialog::new()
+        .set_title("Select Packages to install")
+        .add_filter("Debian packages", &["deb"])
+        .add_filter("Debian source packages", &["dsc"])
+        .pick_files()
+        .await?;
+
+    Some(
+        files
+            .iter()
+            .map(|file| file.path().to_string_lossy().into_owned())
+            .collect(),
+    )
+}
+
+/// Converts the URIs returned by the file chooser into local paths.
+///
+/// Every returned URI is added as its own package, even if the portal returns more files
+/// than were asked for. URIs which don't point to a local file are skipped.
+pub fn selected_paths(uris: &[Url]) -> Vec<String> {
+    uris.iter()
+        .filter_map(|uri| match uri.to_file_path() {
+            Ok(path) => Some(path.to_string_lossy().into_owned()),
+            Err(()) => {
+                eprintln!("ignoring non-local file {uri}");
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_every_local_uri() {
+        let uris = [
+            Url::parse("file:///home/user/first.deb").unwrap(),
+            Url::parse("file:///home/user/second%20package.deb").unwrap(),
+            Url::parse("https://example.com/remote.deb").unwrap(),
+        ];
+
+        assert_eq!(
+            selected_paths(&uris),
+            ["/home/user/first.deb", "/home/user/second package.deb"]
+        );
+    }
+}
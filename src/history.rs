@@ -0,0 +1,153 @@
+//! A persistent record of the packages installed with Wizard.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::package::Package;
+
+/// Where an installed package came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InstallSource {
+    /// Sideloaded from a local file, which apt won't upgrade later.
+    LocalFile(String),
+    /// Installed from the configured repositories.
+    Repository,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub name: String,
+    pub version: String,
+    pub architecture: String,
+    pub source: InstallSource,
+    /// When the install finished, in seconds since the Unix epoch.
+    pub timestamp: i64,
+    pub succeeded: bool,
+    /// The version the install replaced, for upgrades and downgrades.
+    #[serde(default)]
+    pub previous_version: Option<String>,
+    /// Why the install failed, if it did.
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+impl HistoryEntry {
+    /// Records an install of a local package file.
+    pub fn local(package: &Package, succeeded: bool) -> Self {
+        Self {
+            name: package.name.clone(),
+            version: package.version.clone(),
+            architecture: package.architecture.clone(),
+            source: InstallSource::LocalFile(package.path.clone()),
+            timestamp: now(),
+            succeeded,
+            previous_version: None,
+            notes: None,
+        }
+    }
+
+    /// Records an install of `package`, from the repositories if a repository version was
+    /// chosen instead of the local file.
+    pub fn installed(package: &Package, succeeded: bool) -> Self {
+        match &package.repository_id {
+            Some(package_id) => Self::repository(package_id, succeeded),
+            None => Self::local(package, succeeded),
+        }
+    }
+
+    /// Records an install of a package from the repositories, given its PackageKit id.
+    pub fn repository(package_id: &str, succeeded: bool) -> Self {
+        let mut parts = package_id.split(';');
+
+        Self {
+            name: parts.next().unwrap_or_default().to_string(),
+            version: parts.next().unwrap_or_default().to_string(),
+            architecture: parts.next().unwrap_or_default().to_string(),
+            source: InstallSource::Repository,
+            timestamp: now(),
+            succeeded,
+            previous_version: None,
+            notes: None,
+        }
+    }
+
+    /// Records the version the install replaced, if it replaced another one.
+    pub fn replacing(mut self, previous_version: Option<String>) -> Self {
+        self.previous_version = previous_version;
+        self
+    }
+
+    /// Records why the install failed.
+    pub fn failed_because(mut self, reason: String) -> Self {
+        self.notes = Some(reason);
+        self
+    }
+
+    pub fn is_local(&self) -> bool {
+        matches!(self.source, InstallSource::LocalFile(_))
+    }
+
+    /// The local time of the install, formatted for display.
+    pub fn date(&self) -> String {
+        chrono::DateTime::from_timestamp(self.timestamp, 0)
+            .map(|date| {
+                date.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Serializes `entries` for exporting, as a JSON array.
+pub fn to_json(entries: &[HistoryEntry]) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(entries)?)
+}
+
+/// Parses entries exported by [`to_json`].
+pub fn from_json(text: &str) -> anyhow::Result<Vec<HistoryEntry>> {
+    Ok(serde_json::from_str(text)?)
+}
+
+/// The packages `entries` installed successfully, each at the last version installed,
+/// for installing the same set on another machine.
+pub fn installed_packages(entries: &[HistoryEntry]) -> Vec<(String, String)> {
+    let mut packages: Vec<(String, String)> = Vec::new();
+    for entry in entries.iter().filter(|entry| entry.succeeded) {
+        match packages.iter_mut().find(|(name, _)| *name == entry.name) {
+            Some((_, version)) => version.clone_from(&entry.version),
+            None => packages.push((entry.name.clone(), entry.version.clone())),
+        }
+    }
+    packages
+}
+
+/// Adds the `imported` entries missing from `history`, keeping it in chronological order.
+///
+/// Entries are the same when they record the same package at the same time, so importing
+/// a file twice doesn't add anything the second time. Returns the number of entries added.
+pub fn merge(history: &mut Vec<HistoryEntry>, imported: Vec<HistoryEntry>) -> usize {
+    let before = history.len();
+    for entry in imported {
+        let duplicate = history.iter().any(|existing| {
+            existing.timestamp == entry.timestamp
+                && existing.name == entry.name
+                && existing.version == entry.version
+                && existing.architecture == entry.architecture
+        });
+        if !duplicate {
+            history.push(entry);
+        }
+    }
+
+    history.sort_by_key(|entry| entry.timestamp);
+    history.len() - before
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default()
+}
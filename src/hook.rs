@@ -0,0 +1,37 @@
+//! Runs the command configured to follow a successful install.
+
+use std::process::Command;
+
+use anyhow::bail;
+
+/// Runs `command` with `sh -c`, as root through pkexec when `as_root` is set.
+///
+/// The command's output is written to the application's own output, line by line.
+pub fn run(command: &str, as_root: bool) -> anyhow::Result<()> {
+    let mut process = if as_root {
+        let mut process = Command::new("pkexec");
+        process.arg("sh");
+        process
+    } else {
+        Command::new("sh")
+    };
+
+    println!("running post-install command {command:?}");
+    let output = process.arg("-c").arg(command).output()?;
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        println!("post-install: {line}");
+    }
+    for line in String::from_utf8_lossy(&output.stderr).lines() {
+        eprintln!("post-install: {line}");
+    }
+
+    if !output.status.success() {
+        bail!(
+            "post-install command {command:?} failed with {}",
+            output.status
+        );
+    }
+
+    Ok(())
+}
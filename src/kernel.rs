@@ -0,0 +1,65 @@
+//! Detection of packages that are tied to a specific kernel release, such as kernel
+//! images, prebuilt modules and DKMS drivers.
+
+use std::fs;
+
+use crate::control::Relation;
+
+/// Prefixes of package names that belong to a kernel release.
+const KERNEL_PREFIXES: &[&str] = &["linux-image", "linux-headers", "linux-modules"];
+
+/// The release of the running kernel, as printed by `uname -r`.
+pub fn running_release() -> Option<String> {
+    fs::read_to_string("/proc/sys/kernel/osrelease")
+        .ok()
+        .map(|release| release.trim().to_string())
+}
+
+/// Whether a package, or a dependency on it, is tied to the kernel.
+pub fn is_kernel_related(name: &str) -> bool {
+    name == "dkms"
+        || KERNEL_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+}
+
+/// Extracts the kernel release a package name targets, such as `6.8.0-31-generic` from
+/// `linux-modules-nvidia-550-6.8.0-31-generic`.
+///
+/// Meta packages like `linux-image-generic` don't target a single release.
+pub fn target_release(name: &str) -> Option<&str> {
+    if !KERNEL_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+    {
+        return None;
+    }
+
+    // The release is the first dash separated part that looks like `major.minor`.
+    let mut offset = 0;
+    for part in name.split('-') {
+        if part.starts_with(|c: char| c.is_ascii_digit()) && part.contains('.') {
+            return Some(&name[offset..]);
+        }
+        offset += part.len() + 1;
+    }
+
+    None
+}
+
+/// Kernel releases targeted by the package or its dependencies that differ from the
+/// running kernel.
+pub fn mismatched_releases<'a>(
+    name: &'a str,
+    depends: impl IntoIterator<Item = &'a Relation>,
+    running: &str,
+) -> Vec<&'a str> {
+    let mut releases: Vec<&str> = std::iter::once(name)
+        .chain(depends.into_iter().map(|relation| relation.name.as_str()))
+        .filter_map(target_release)
+        .filter(|release| *release != running)
+        .collect();
+    releases.sort_unstable();
+    releases.dedup();
+    releases
+}
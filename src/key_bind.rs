@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+
+use cosmic::iced::keyboard::key::Named;
+use cosmic::iced::keyboard::Key;
+use cosmic::widget::menu::key_bind::{KeyBind, Modifier};
+
+use crate::app::MenuAction;
+
+/// Keyboard shortcuts for the application's menu actions.
+pub fn key_binds() -> HashMap<KeyBind, MenuAction> {
+    let mut key_binds = HashMap::new();
+
+    macro_rules! bind {
+        ([$($modifier:ident),* $(,)?], $key:expr, $action:ident) => {{
+            key_binds.insert(
+                KeyBind {
+                    modifiers: vec![$(Modifier::$modifier),*],
+                    key: $key,
+                },
+                MenuAction::$action,
+            );
+        }};
+    }
+
+    bind!([], Key::Named(Named::F1), About);
+
+    key_binds
+}
@@ -0,0 +1,63 @@
+//! Coordination with other package managers, mainly unattended-upgrades, which hold the
+//! dpkg frontend lock while they run.
+
+use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::fl;
+use crate::packagekit::TransactionError;
+
+/// How often to check whether automatic updates have finished.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// How long to wait for automatic updates before giving up.
+pub const WAIT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// How long to wait before retrying when another package manager holds the lock.
+pub const RETRY_DELAY: Duration = Duration::from_secs(15);
+
+/// Whether unattended-upgrades is currently running.
+pub fn unattended_upgrades_running() -> bool {
+    let Ok(processes) = fs::read_dir("/proc") else {
+        return false;
+    };
+
+    processes.flatten().any(|process| {
+        fs::read(process.path().join("cmdline")).is_ok_and(|cmdline| {
+            // The arguments are NUL separated, the script is run by the Python interpreter.
+            cmdline
+                .split(|&byte| byte == 0)
+                .take(2)
+                .any(|arg| arg.ends_with(b"unattended-upgrade"))
+        })
+    })
+}
+
+/// Blocks until unattended-upgrades has finished, or `timeout` elapsed.
+///
+/// Returns whether it finished.
+pub fn wait_for_unattended_upgrades(timeout: Duration) -> bool {
+    let start = Instant::now();
+    while unattended_upgrades_running() {
+        if start.elapsed() >= timeout {
+            return false;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    true
+}
+
+/// Whether a transaction failed because another package manager holds the lock.
+pub fn is_lock_error(why: &anyhow::Error) -> bool {
+    why.downcast_ref::<TransactionError>()
+        .is_some_and(TransactionError::is_lock_error)
+}
+
+/// Explains a lock error without the backend's jargon.
+pub fn describe_busy() -> String {
+    if unattended_upgrades_running() {
+        fl!("busy-automatic-updates")
+    } else {
+        fl!("busy-package-manager")
+    }
+}
@@ -2,9 +2,24 @@
 
 mod app;
 mod config;
+mod control;
+mod deb;
+mod debconf;
+mod file_dialog;
+mod history;
+mod hook;
 mod i18n;
+mod kernel;
+mod key_bind;
+mod lock;
 mod package;
 mod packagekit;
+mod polkit;
+mod preflight;
+mod sources;
+mod terminal;
+mod tray;
+mod version;
 
 fn main() -> cosmic::iced::Result {
     // Get the system's preferred languages.
@@ -17,5 +32,10 @@ fn main() -> cosmic::iced::Result {
     let settings = cosmic::app::Settings::default();
 
     // Starts the application's event loop with `()` as the application's flags.
-    cosmic::app::run::<app::AppModel>(settings, ())
+    let result = cosmic::app::run::<app::AppModel>(settings, ());
+
+    // The window may close without `Message::Quit`, such as when the event loop fails.
+    deb::remove_buffered_stdin();
+
+    result
 }
@@ -1,4 +1,46 @@
-use crate::packagekit::{PackageKit, TransactionDetails};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::process::Command;
+use std::rc::Rc;
+
+use anyhow::{anyhow, bail};
+
+use crate::control::{parse_relations, Paragraph, Relation};
+use crate::deb;
+use crate::fl;
+use crate::kernel;
+use crate::lock;
+use crate::packagekit::{
+    self, Filter, InstallOptions, ItemProgress, PackageKit, Status, TransactionDetails,
+    TransactionError, TransactionPackage,
+};
+use crate::polkit;
+use crate::sources::{self, Origin};
+use crate::version;
+
+#[derive(Debug, Clone)]
+pub enum PackageKind {
+    /// A binary package that can be installed.
+    Binary,
+    /// A source package, described by a `.dsc` file, which can only have its build
+    /// dependencies installed.
+    Source { build_depends: Vec<Vec<Relation>> },
+}
+
+/// How installing a package would change the system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallKind {
+    /// The package isn't installed yet.
+    Install,
+    /// An older version is installed.
+    Upgrade,
+    /// A newer version is installed.
+    Downgrade,
+    /// The same version is installed, so there is nothing to do unless reinstalling.
+    AlreadyInstalled,
+}
 
 #[derive(Debug, Clone)]
 pub struct Package {
@@ -10,8 +52,45 @@ pub struct Package {
     pub summary: String,
     pub description: String,
     pub url: String,
+    /// Where to report bugs, from the `Bugs` field of the control file.
+    pub bugs: Option<String>,
+    /// Web view of the packaging repository, from the `Vcs-Browser` field.
+    pub vcs_browser: Option<String>,
     pub license: String,
+    pub maintainer: String,
     pub size: String,
+    /// Installed size in bytes, or 0 when unknown.
+    pub installed_size: u64,
+    /// Whether [`Package::installed_size`] was estimated from the data archive, because
+    /// the control file has no `Installed-Size` field.
+    pub installed_size_estimated: bool,
+    /// Version of the package currently installed on the system, if any.
+    pub installed_version: Option<String>,
+    pub depends: Vec<Vec<Relation>>,
+    /// Whether the package is marked `Essential` or `Protected`, so removing it could
+    /// leave the system unusable.
+    pub essential: bool,
+    /// Every field of the control file, verbatim and in order.
+    pub control_fields: Vec<(String, String)>,
+    /// Configuration files shipped by the package, kept by dpkg across upgrades.
+    pub conffiles: Vec<String>,
+    /// dpkg triggers the package activates, which run their processing after the install.
+    pub triggers: Vec<String>,
+    /// Packages that must be configured before this one can even be unpacked.
+    pub pre_depends: Vec<Vec<Relation>>,
+    /// Other versions of the package offered by the configured repositories, as
+    /// PackageKit ids.
+    pub repository_versions: Vec<String>,
+    /// Repository version chosen to install instead of the local file, if any.
+    pub repository_id: Option<String>,
+    /// Sources of [`Package::repository_versions`], keyed by PackageKit id.
+    pub repository_origins: HashMap<String, Origin>,
+    /// Installed versions of the packages named by the dependencies, keyed by name.
+    pub installed_dependencies: HashMap<String, String>,
+    /// Installed packages providing the virtual packages named by the dependencies,
+    /// keyed by virtual package name.
+    pub installed_providers: HashMap<String, Vec<Provider>>,
+    pub kind: PackageKind,
 }
 
 impl Package {
@@ -30,26 +109,1041 @@ impl Package {
             summary: tx.summary,
             description: tx.description,
             url: tx.url,
+            bugs: None,
+            vcs_browser: None,
             license: tx.license,
+            maintainer: String::new(),
             size: tx.size,
+            installed_size: tx.installed_size,
+            installed_size_estimated: false,
+            installed_version: None,
+            essential: false,
+            control_fields: Vec::new(),
+            conffiles: Vec::new(),
+            triggers: Vec::new(),
+            depends: Vec::new(),
+            pre_depends: Vec::new(),
+            repository_versions: Vec::new(),
+            repository_id: None,
+            repository_origins: HashMap::new(),
+            installed_dependencies: HashMap::new(),
+            installed_providers: HashMap::new(),
+            kind: PackageKind::Binary,
+        }
+    }
+
+    /// Reads a source package description from a `.dsc` file.
+    pub fn from_dsc(path: String) -> anyhow::Result<Self> {
+        let text = fs::read_to_string(&path)?;
+        let paragraph = Paragraph::parse(&text);
+
+        let name = paragraph
+            .get("Source")
+            .ok_or_else(|| anyhow!("missing Source field in {path}"))?
+            .to_string();
+        let version = paragraph.get("Version").unwrap_or_default().to_string();
+
+        let build_depends = ["Build-Depends", "Build-Depends-Arch", "Build-Depends-Indep"]
+            .iter()
+            .filter_map(|field| paragraph.get(field))
+            .flat_map(parse_relations)
+            .collect();
+
+        Ok(Self {
+            path,
+            id: format!("{name};{version};source;"),
+            name,
+            version,
+            architecture: paragraph
+                .get("Architecture")
+                .unwrap_or_default()
+                .to_string(),
+            summary: String::new(),
+            description: String::new(),
+            url: paragraph.get("Homepage").unwrap_or_default().to_string(),
+            bugs: None,
+            vcs_browser: paragraph.get("Vcs-Browser").map(str::to_string),
+            license: String::new(),
+            maintainer: paragraph.get("Maintainer").unwrap_or_default().to_string(),
+            size: String::new(),
+            installed_size: 0,
+            installed_size_estimated: false,
+            installed_version: None,
+            essential: false,
+            control_fields: control_fields(&paragraph),
+            conffiles: Vec::new(),
+            triggers: Vec::new(),
+            depends: Vec::new(),
+            pre_depends: Vec::new(),
+            repository_versions: Vec::new(),
+            repository_id: None,
+            repository_origins: HashMap::new(),
+            installed_dependencies: HashMap::new(),
+            installed_providers: HashMap::new(),
+            kind: PackageKind::Source { build_depends },
+        })
+    }
+
+    /// The version that will be installed, from the chosen repository version if any.
+    pub fn selected_version(&self) -> &str {
+        self.repository_id
+            .as_deref()
+            .map(id_version)
+            .unwrap_or(&self.version)
+    }
+
+    /// The source of the chosen repository version, or `None` for the local file.
+    pub fn selected_origin(&self) -> Option<&Origin> {
+        self.repository_id
+            .as_ref()
+            .and_then(|id| self.repository_origins.get(id))
+    }
+
+    pub fn is_source(&self) -> bool {
+        matches!(self.kind, PackageKind::Source { .. })
+    }
+
+    /// Whether any alternative of a dependency group is installed at a suitable version,
+    /// or provided by an installed package.
+    pub fn is_satisfied(&self, group: &[Relation]) -> bool {
+        group.iter().any(|relation| {
+            self.installed_dependencies
+                .get(&relation.name)
+                .is_some_and(|version| relation.is_satisfied_by(version))
+                || self.provider_of(relation).is_some()
+        })
+    }
+
+    /// An installed package providing the virtual package named by `relation`.
+    ///
+    /// Versioned relations are only satisfied by versioned provides, as dpkg does.
+    pub fn provider_of(&self, relation: &Relation) -> Option<&Provider> {
+        self.installed_providers
+            .get(&relation.name)?
+            .iter()
+            .find(|provider| match (&relation.constraint, &provider.version) {
+                (None, _) => true,
+                (Some(_), Some(version)) => relation.is_satisfied_by(version),
+                (Some(_), None) => false,
+            })
+    }
+
+    /// Pre-dependencies that aren't satisfied by the installed packages.
+    pub fn unsatisfied_pre_depends(&self) -> Vec<&[Relation]> {
+        self.pre_depends
+            .iter()
+            .map(Vec::as_slice)
+            .filter(|group| !self.is_satisfied(group))
+            .collect()
+    }
+
+    /// Reads the links, dependency fields, conffiles and triggers from the package's control archive.
+    fn read_control(&mut self, proxy: &PackageKit) -> anyhow::Result<()> {
+        let control = deb::read_control(&self.path)?;
+        self.bugs = control.get("Bugs").map(str::to_string);
+        self.vcs_browser = control.get("Vcs-Browser").map(str::to_string);
+        self.maintainer = control.get("Maintainer").unwrap_or_default().to_string();
+        // Installed-Size is in KiB, and more accurate than what PackageKit estimates.
+        if let Some(kib) = control
+            .get("Installed-Size")
+            .and_then(|size| size.trim().parse::<u64>().ok())
+        {
+            self.installed_size = kib * 1024;
+        } else {
+            match deb::data_size(&self.path) {
+                Ok(size) => {
+                    self.installed_size = size;
+                    self.installed_size_estimated = true;
+                }
+                Err(why) => eprintln!("failed to estimate the size of {}: {why}", self.path),
+            }
         }
+        self.essential = ["Essential", "Protected"]
+            .iter()
+            .any(|field| control.get(field).is_some_and(|value| value == "yes"));
+        self.control_fields = control_fields(&control);
+        self.conffiles = deb::read_conffiles(&self.path)?;
+        self.triggers = deb::read_triggers(&self.path)?;
+        self.depends = control
+            .get("Depends")
+            .map(parse_relations)
+            .unwrap_or_default();
+        self.pre_depends = control
+            .get("Pre-Depends")
+            .map(parse_relations)
+            .unwrap_or_default();
+
+        let names: Vec<&str> = self
+            .depends
+            .iter()
+            .chain(&self.pre_depends)
+            .flatten()
+            .map(|relation| relation.name.as_str())
+            .collect();
+        self.installed_dependencies = proxy.installed_versions(&names)?;
+        self.installed_providers = installed_providers(&names)?;
+
+        Ok(())
+    }
+
+    /// Dependency groups naming the kernel, its headers or modules, or DKMS.
+    pub fn kernel_depends(&self) -> Vec<&[Relation]> {
+        self.pre_depends
+            .iter()
+            .chain(&self.depends)
+            .map(Vec::as_slice)
+            .filter(|group| {
+                group
+                    .iter()
+                    .any(|relation| kernel::is_kernel_related(&relation.name))
+            })
+            .collect()
+    }
+
+    /// Kernel releases this package targets other than the running one.
+    pub fn mismatched_kernel_releases(&self, running: &str) -> Vec<&str> {
+        kernel::mismatched_releases(
+            &self.name,
+            self.pre_depends.iter().chain(&self.depends).flatten(),
+            running,
+        )
+    }
+
+    pub fn install_kind(&self) -> InstallKind {
+        match &self.installed_version {
+            None => InstallKind::Install,
+            Some(installed) => match version::compare(self.selected_version(), installed) {
+                Ordering::Greater => InstallKind::Upgrade,
+                Ordering::Less => InstallKind::Downgrade,
+                Ordering::Equal => InstallKind::AlreadyInstalled,
+            },
+        }
+    }
+}
+
+fn control_fields(paragraph: &Paragraph) -> Vec<(String, String)> {
+    paragraph
+        .fields()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Reads the packages described by a local file.
+pub fn load_packages(path: String) -> anyhow::Result<Vec<Package>> {
+    if path.ends_with(".dsc") {
+        return Ok(vec![Package::from_dsc(path)?]);
+    }
+
+    deb::validate(&path)?;
+
+    let proxy = PackageKit::new()?;
+    let tx_details = proxy.get_details_local(&[&path])?;
+
+    let mut packages = Vec::with_capacity(tx_details.len());
+    for tx_detail in tx_details {
+        let mut package = Package::new(path.clone(), tx_detail);
+        match proxy.installed_version(&package.name, &package.architecture) {
+            Ok(version) => package.installed_version = version,
+            Err(why) => eprintln!("failed to find the installed version of {path}: {why}"),
+        }
+        if let Err(why) = package.read_control(&proxy) {
+            eprintln!("failed to read the control file of {path}: {why}");
+        }
+        match proxy.repository_versions(&package.name, &package.architecture, &package.version) {
+            Ok(ids) => package.repository_versions = ids,
+            Err(why) => eprintln!("failed to find repository versions of {path}: {why}"),
+        }
+        package.repository_origins = sources::origins(&package.repository_versions);
+        // Default to the newest version available, which may be in the repositories.
+        package.repository_id = package
+            .repository_versions
+            .iter()
+            .max_by(|a, b| version::compare(id_version(a), id_version(b)))
+            .filter(|id| version::compare(id_version(id), &package.version).is_gt())
+            .cloned();
+        packages.push(package);
+    }
+
+    Ok(packages)
+}
+
+/// The version component of a PackageKit package id.
+fn id_version(package_id: &str) -> &str {
+    package_id.split(';').nth(1).unwrap_or_default()
+}
+
+/// The result of installing a batch of packages.
+#[derive(Debug, Clone, Default)]
+pub struct InstallSummary {
+    pub installed: Vec<Package>,
+    /// Packages that failed to install, with the reason reported by the backend.
+    pub failed: Vec<(Package, String)>,
+    /// Packages that weren't attempted because an earlier one failed.
+    pub skipped: Vec<Package>,
+    /// How the install changed the versions of installed packages, keyed by package id.
+    /// Fresh installs and reinstalls of the same version are left out.
+    pub version_changes: HashMap<String, VersionChange>,
+}
+
+/// The installed versions of a package before and after an install.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionChange {
+    pub from: String,
+    pub to: String,
+}
+
+impl VersionChange {
+    pub fn is_downgrade(&self) -> bool {
+        version::compare(&self.to, &self.from).is_lt()
+    }
+}
+
+impl InstallSummary {
+    pub fn succeeded(&self) -> bool {
+        self.failed.is_empty() && self.skipped.is_empty()
+    }
+
+    /// Number of packages in the batch.
+    pub fn total(&self) -> usize {
+        self.installed.len() + self.failed.len() + self.skipped.len()
     }
 }
 
+/// Installs the binary packages among `packages`.
+///
+/// Every package is installed in a single transaction first, which needs one authorization
+/// and resolves dependencies across the whole set. If that fails for another reason than
+/// a refused authorization, and `stop_on_error` isn't set, the packages are retried one
+/// transaction per package so that one broken file doesn't block the others. Packages
+/// that are already installed at the same version are reinstalled, so callers should only
+/// pass those when the user asked for it.
+///
+/// `options` applies to every package, except that reinstalls and downgrades are allowed
+/// per package as needed. Packages with a chosen repository version are installed from the
+/// repositories first, unless `options` asks for something only dpkg can do.
 pub fn install_packages_local(
     packages: Vec<Package>,
-    f: Box<dyn FnMut(u32) + 'static>,
-) -> anyhow::Result<bool> {
-    let mut paths = Vec::with_capacity(packages.len());
+    options: InstallOptions,
+    stop_on_error: bool,
+    f: Box<dyn FnMut(ItemProgress) + 'static>,
+) -> InstallSummary {
+    packagekit::reset_cancel();
+    let packages: Vec<Package> = packages
+        .into_iter()
+        .filter(|package| !package.is_source())
+        .collect();
+    let f = Rc::new(RefCell::new(f));
+
+    let proxy = match PackageKit::new() {
+        Ok(proxy) => proxy,
+        Err(why) => {
+            let why = why.to_string();
+            return InstallSummary {
+                failed: packages
+                    .into_iter()
+                    .map(|package| (package, why.clone()))
+                    .collect(),
+                ..InstallSummary::default()
+            };
+        }
+    };
 
-    packages
+    // Compared with the versions installed afterwards, to report upgrades and downgrades.
+    let names: Vec<&str> = packages
         .iter()
-        .for_each(|package| paths.push(package.path.as_str()));
+        .map(|package| package.name.as_str())
+        .collect();
+    let before = proxy.installed_versions(&names).unwrap_or_else(|why| {
+        eprintln!("failed to read the installed versions: {why}");
+        HashMap::new()
+    });
 
-    let proxy = PackageKit::new()?;
-    if proxy.install_packages_files(&paths, f).is_ok() {
-        Ok(true)
+    let from_repository = options.root.is_none() && !options.ignore_dependencies;
+    let (repository, files): (Vec<Package>, Vec<Package>) = packages
+        .into_iter()
+        .partition(|package| from_repository && package.repository_id.is_some());
+
+    let mut summary = install_repository_versions(&proxy, repository, options.allow_untrusted, &f);
+    if (stop_on_error && !summary.failed.is_empty()) || packagekit::cancel_requested() {
+        summary.skipped.extend(files);
     } else {
-        Ok(false)
+        let files = install_files(&proxy, files, &options, stop_on_error, f);
+        summary.installed.extend(files.installed);
+        summary.failed.extend(files.failed);
+        summary.skipped.extend(files.skipped);
+    }
+
+    summary.version_changes = version_changes(&proxy, &summary.installed, &before);
+    summary
+}
+
+/// Compares the versions of `installed` from before the install with the current ones.
+fn version_changes(
+    proxy: &PackageKit,
+    installed: &[Package],
+    before: &HashMap<String, String>,
+) -> HashMap<String, VersionChange> {
+    let names: Vec<&str> = installed
+        .iter()
+        .map(|package| package.name.as_str())
+        .collect();
+    let after = match proxy.installed_versions(&names) {
+        Ok(after) => after,
+        Err(why) => {
+            eprintln!("failed to read the installed versions: {why}");
+            return HashMap::new();
+        }
+    };
+
+    installed
+        .iter()
+        .filter_map(|package| {
+            let from = before.get(&package.name)?;
+            let to = after.get(&package.name)?;
+            (from != to).then(|| {
+                let change = VersionChange {
+                    from: from.clone(),
+                    to: to.clone(),
+                };
+                (package.id.clone(), change)
+            })
+        })
+        .collect()
+}
+
+/// Installs the repository versions chosen for `packages`, in a single transaction.
+fn install_repository_versions(
+    proxy: &PackageKit,
+    packages: Vec<Package>,
+    allow_untrusted: bool,
+    f: &Rc<RefCell<Box<dyn FnMut(ItemProgress) + 'static>>>,
+) -> InstallSummary {
+    let ids: Vec<&str> = packages
+        .iter()
+        .filter_map(|package| package.repository_id.as_deref())
+        .collect();
+    let Some(&first) = ids.first() else {
+        return InstallSummary::default();
+    };
+
+    // The transaction asks for authorization before reporting any progress.
+    (f.borrow_mut())(ItemProgress {
+        package_id: first.to_string(),
+        status: waiting_status(proxy),
+        percentage: 0,
+        total_percentage: 0,
+    });
+
+    let options = InstallOptions {
+        allow_untrusted,
+        allow_reinstall: packages
+            .iter()
+            .any(|package| package.install_kind() == InstallKind::AlreadyInstalled),
+        allow_downgrade: packages
+            .iter()
+            .any(|package| package.install_kind() == InstallKind::Downgrade),
+        ..InstallOptions::default()
+    };
+
+    let result = retry_on_lock(f, first, 0, || {
+        let f = f.clone();
+        proxy.install_packages(
+            &ids,
+            options.clone(),
+            Box::new(move |progress| (f.borrow_mut())(progress)),
+        )
+    });
+
+    match result {
+        Ok(()) => InstallSummary {
+            installed: packages,
+            ..InstallSummary::default()
+        },
+        Err(why) => {
+            eprintln!("failed to install {ids:?}: {why}");
+            let why = failure_reason(&why, polkit::INSTALL_ACTION);
+            InstallSummary {
+                failed: packages
+                    .into_iter()
+                    .map(|package| (package, why.clone()))
+                    .collect(),
+                ..InstallSummary::default()
+            }
+        }
+    }
+}
+
+/// Installs local package files, in one transaction when possible.
+fn install_files(
+    proxy: &PackageKit,
+    packages: Vec<Package>,
+    options: &InstallOptions,
+    stop_on_error: bool,
+    f: Rc<RefCell<Box<dyn FnMut(ItemProgress) + 'static>>>,
+) -> InstallSummary {
+    let action = polkit::install_action(options.allow_untrusted);
+
+    // dpkg only installs one file at a time when ignoring dependencies.
+    if packages.len() < 2 || options.ignore_dependencies {
+        return install_each(proxy, packages, options, stop_on_error, f);
+    }
+
+    let paths: Vec<&str> = packages
+        .iter()
+        .map(|package| package.path.as_str())
+        .collect();
+    let batch_options = InstallOptions {
+        allow_reinstall: packages
+            .iter()
+            .any(|package| package.install_kind() == InstallKind::AlreadyInstalled),
+        allow_downgrade: packages
+            .iter()
+            .any(|package| package.install_kind() == InstallKind::Downgrade),
+        ..options.clone()
+    };
+
+    // The transaction asks for authorization before reporting any progress.
+    (f.borrow_mut())(ItemProgress {
+        package_id: packages[0].id.clone(),
+        status: waiting_status(proxy),
+        percentage: 0,
+        total_percentage: 0,
+    });
+
+    let result = retry_on_lock(&f, &packages[0].id, 0, || {
+        let batch_f = f.clone();
+        let progress = Box::new(move |progress| (batch_f.borrow_mut())(progress));
+        proxy.install_packages_files(&paths, batch_options.clone(), progress)
+    });
+
+    let why = match result {
+        Ok(()) => {
+            return InstallSummary {
+                installed: packages,
+                ..InstallSummary::default()
+            }
+        }
+        Err(why) => why,
+    };
+    eprintln!("failed to install {paths:?} in one transaction: {why}");
+
+    let not_authorized = why
+        .downcast_ref::<TransactionError>()
+        .is_some_and(TransactionError::is_not_authorized);
+    if stop_on_error || not_authorized || packagekit::cancel_requested() {
+        let why = failure_reason(&why, action);
+        return InstallSummary {
+            failed: packages
+                .into_iter()
+                .map(|package| (package, why.clone()))
+                .collect(),
+            ..InstallSummary::default()
+        };
     }
+
+    install_each(proxy, packages, options, stop_on_error, f)
+}
+
+/// Installs `packages` one transaction per package, in order.
+///
+/// When `stop_on_error` is set, the packages after the first failure are skipped,
+/// otherwise every package is attempted.
+fn install_each(
+    proxy: &PackageKit,
+    packages: Vec<Package>,
+    options: &InstallOptions,
+    stop_on_error: bool,
+    f: Rc<RefCell<Box<dyn FnMut(ItemProgress) + 'static>>>,
+) -> InstallSummary {
+    let total = packages.len().max(1) as u32;
+    let mut summary = InstallSummary::default();
+    let action = polkit::install_action(options.allow_untrusted);
+
+    for (index, package) in (0..).zip(packages) {
+        if (stop_on_error && !summary.failed.is_empty()) || packagekit::cancel_requested() {
+            summary.skipped.push(package);
+            continue;
+        }
+
+        let options = InstallOptions {
+            allow_reinstall: package.install_kind() == InstallKind::AlreadyInstalled,
+            allow_downgrade: package.install_kind() == InstallKind::Downgrade,
+            ..options.clone()
+        };
+
+        // Every transaction asks for authorization before reporting any progress.
+        let done = index * 100 / total;
+        (f.borrow_mut())(ItemProgress {
+            package_id: package.id.clone(),
+            status: waiting_status(proxy),
+            percentage: 0,
+            total_percentage: done,
+        });
+
+        let result = retry_on_lock(&f, &package.id, done, || {
+            if options.ignore_dependencies {
+                return install_ignoring_depends(&package.path, &options);
+            }
+
+            let f = f.clone();
+            let progress = Box::new(move |mut progress: ItemProgress| {
+                // Report the progress of the whole batch rather than of this transaction.
+                if progress.total_percentage <= 100 {
+                    progress.total_percentage = (index * 100 + progress.total_percentage) / total;
+                }
+                (f.borrow_mut())(progress);
+            });
+
+            proxy.install_packages_files(&[&package.path], options.clone(), progress)
+        });
+
+        match result {
+            Ok(()) => summary.installed.push(package),
+            Err(why) => {
+                eprintln!("failed to install {}: {why}", package.path);
+                summary.failed.push((package, failure_reason(&why, action)));
+            }
+        }
+    }
+
+    summary
+}
+
+/// Runs `install`, first waiting for unattended-upgrades to finish, and retrying once
+/// if another package manager held the lock anyway.
+///
+/// The wait is reported to `f` as the package `package_id` waiting for the lock, with
+/// the progress of the batch staying at `total_percentage`.
+fn retry_on_lock(
+    f: &Rc<RefCell<Box<dyn FnMut(ItemProgress) + 'static>>>,
+    package_id: &str,
+    total_percentage: u32,
+    mut install: impl FnMut() -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let report_wait = || {
+        (f.borrow_mut())(ItemProgress {
+            package_id: package_id.to_string(),
+            status: Status::WaitingForLock as u32,
+            percentage: 0,
+            total_percentage,
+        });
+    };
+
+    if lock::unattended_upgrades_running() {
+        report_wait();
+        lock::wait_for_unattended_upgrades(lock::WAIT_TIMEOUT);
+    }
+
+    match install() {
+        Err(why) if lock::is_lock_error(&why) => {
+            eprintln!("package manager is busy, retrying: {why}");
+            report_wait();
+            if !lock::wait_for_unattended_upgrades(lock::WAIT_TIMEOUT) {
+                return Err(why);
+            }
+            std::thread::sleep(lock::RETRY_DELAY);
+            install()
+        }
+        result => result,
+    }
+}
+
+/// Describes why an install failed, in terms the user can act on.
+fn failure_reason(why: &anyhow::Error, action: &str) -> String {
+    let cancelled = why
+        .downcast_ref::<TransactionError>()
+        .is_some_and(TransactionError::is_cancelled);
+    if cancelled {
+        fl!("install-cancelled")
+    } else if lock::is_lock_error(why) {
+        lock::describe_busy()
+    } else {
+        polkit::describe_failure(why, action)
+    }
+}
+
+/// What a new transaction waits for before it reports any progress: other transactions
+/// when the daemon is busy, otherwise the user's authorization.
+fn waiting_status(proxy: &PackageKit) -> u32 {
+    match proxy.is_busy() {
+        Ok(true) => Status::Wait as u32,
+        Ok(false) => Status::WaitingForAuth as u32,
+        Err(why) => {
+            eprintln!("failed to list running transactions: {why}");
+            Status::WaitingForAuth as u32
+        }
+    }
+}
+
+/// Installs a package file with `dpkg --force-depends`, which PackageKit can't do.
+///
+/// The package is unpacked and configured even if its dependencies are missing, which
+/// leaves dpkg reporting broken packages until they are installed.
+fn install_ignoring_depends(path: &str, options: &InstallOptions) -> anyhow::Result<()> {
+    let mut command = Command::new("pkexec");
+    command.args(["dpkg", "--force-depends"]);
+    if let Some(root) = &options.root {
+        command.arg("--root").arg(root);
+    }
+    if options.allow_downgrade {
+        command.arg("--force-downgrade");
+    }
+
+    println!("installing {path} while ignoring dependencies");
+    let output = command.arg("--install").arg(path).output()?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(())
+}
+
+/// How a simulated install would affect the installed packages.
+#[derive(Debug, Default)]
+pub struct Simulation {
+    /// Installed packages the install would remove.
+    pub removals: Vec<String>,
+    /// Installed packages the install would remove, upgrade or downgrade.
+    pub changes: Vec<String>,
+}
+
+/// Simulates installing `packages`, listing the installed packages it would change.
+///
+/// Packages with a chosen repository version are simulated from the repositories, as
+/// they will be installed.
+pub fn simulate_install(packages: Vec<Package>) -> anyhow::Result<Simulation> {
+    let (repository, files): (Vec<&Package>, Vec<&Package>) = packages
+        .iter()
+        .filter(|package| !package.is_source())
+        .partition(|package| package.repository_id.is_some());
+    let ids: Vec<&str> = repository
+        .iter()
+        .filter_map(|package| package.repository_id.as_deref())
+        .collect();
+    let paths: Vec<&str> = files.iter().map(|package| package.path.as_str()).collect();
+
+    let proxy = PackageKit::new()?;
+    let mut simulated = Vec::new();
+    if !ids.is_empty() {
+        simulated.extend(proxy.simulate_install_packages(&ids)?);
+    }
+    if !paths.is_empty() {
+        simulated.extend(proxy.simulate_install_files(&paths)?);
+    }
+
+    let names = |filter: fn(&TransactionPackage) -> bool| {
+        let mut names: Vec<String> = simulated
+            .iter()
+            .filter(|package| filter(package))
+            .map(|package| package.name().to_string())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    };
+
+    Ok(Simulation {
+        removals: names(TransactionPackage::is_removal),
+        changes: names(TransactionPackage::is_change),
+    })
+}
+
+/// Lists the installed packages the user put on hold.
+pub fn held_packages() -> anyhow::Result<Vec<String>> {
+    let output = Command::new("dpkg-query")
+        .args(["--show", "--showformat", "${Package} ${Status}\n"])
+        .output()?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    // The first word of the status is the selection state, set to `hold` by apt-mark.
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?;
+            (fields.next() == Some("hold")).then(|| name.to_string())
+        })
+        .collect())
+}
+
+/// Foreign architectures that can be enabled next to a native one, which the kernel and
+/// libraries of the native architecture can run.
+const FOREIGN_ARCHITECTURES: &[(&str, &[&str])] =
+    &[("amd64", &["i386"]), ("arm64", &["armhf", "armel"])];
+
+/// Whether dpkg can be configured with `dpkg --add-architecture` to install packages
+/// built for `architecture` next to the `native` ones.
+pub fn can_enable_architecture(native: &str, architecture: &str) -> bool {
+    FOREIGN_ARCHITECTURES
+        .iter()
+        .any(|(candidate, foreign)| *candidate == native && foreign.contains(&architecture))
+}
+
+/// Configures dpkg to install packages built for a foreign architecture, then refreshes
+/// the package lists so that the repositories' packages for it can be found.
+pub fn enable_architecture(architecture: &str) -> anyhow::Result<()> {
+    println!("enabling the {architecture} architecture");
+    let output = Command::new("pkexec")
+        .args(["dpkg", "--add-architecture", architecture])
+        .output()?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    PackageKit::new()?.refresh_cache()
+}
+
+/// Lists the architectures dpkg installs packages for, the native one first.
+pub fn supported_architectures() -> anyhow::Result<Vec<String>> {
+    let mut architectures = Vec::new();
+    for flag in ["--print-architecture", "--print-foreign-architectures"] {
+        let output = Command::new("dpkg").arg(flag).output()?;
+        if !output.status.success() {
+            bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        architectures.extend(
+            String::from_utf8_lossy(&output.stdout)
+                .split_whitespace()
+                .map(str::to_string),
+        );
+    }
+
+    Ok(architectures)
+}
+
+/// Filters `names` down to the installed packages marked `Essential` or `Protected`.
+pub fn essential_packages(names: &[String]) -> anyhow::Result<Vec<String>> {
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let output = Command::new("dpkg-query")
+        .args([
+            "--show",
+            "--showformat",
+            "${Package} ${Essential} ${Protected}\n",
+        ])
+        .args(names)
+        .output()?;
+
+    // dpkg-query exits with 1 when some names aren't installed, but still lists the others.
+    if !matches!(output.status.code(), Some(0 | 1)) {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?;
+            fields.any(|flag| flag == "yes").then(|| name.to_string())
+        })
+        .collect())
+}
+
+/// An installed package that provides a virtual package, such as `postfix` providing
+/// `mail-transport-agent`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provider {
+    pub name: String,
+    /// The version of the virtual package, set by versioned provides like `foo (= 1.0)`.
+    pub version: Option<String>,
+}
+
+/// Finds the installed packages providing the virtual packages named by `names`, keyed
+/// by virtual package name.
+pub fn installed_providers(names: &[&str]) -> anyhow::Result<HashMap<String, Vec<Provider>>> {
+    let mut providers: HashMap<String, Vec<Provider>> = HashMap::new();
+    if names.is_empty() {
+        return Ok(providers);
+    }
+
+    let output = Command::new("dpkg-query")
+        .args([
+            "--show",
+            "--showformat",
+            "${db:Status-Abbrev}\t${Package}\t${Provides}\n",
+        ])
+        .output()?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let mut fields = line.split('\t');
+        let (Some(status), Some(name), Some(provides)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        // Packages that were removed but not purged are still listed.
+        if !status.starts_with("ii") {
+            continue;
+        }
+
+        for relation in parse_relations(provides).into_iter().flatten() {
+            if !names.contains(&relation.name.as_str()) {
+                continue;
+            }
+            let version = relation
+                .constraint
+                .as_deref()
+                .map(|constraint| constraint.trim_start_matches('=').trim().to_string());
+            providers.entry(relation.name).or_default().push(Provider {
+                name: name.to_string(),
+                version,
+            });
+        }
+    }
+
+    Ok(providers)
+}
+
+/// Finds the repository packages that could reinstall the packages named by `names`.
+///
+/// Names that aren't available from the configured repositories are left out.
+pub fn reinstall_candidates(names: &[String]) -> anyhow::Result<Vec<String>> {
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+    let proxy = PackageKit::new()?;
+    let filter = Filter::NotInstalled as u64 | Filter::Arch as u64 | Filter::Newest as u64;
+    Ok(proxy
+        .resolve(&names, filter)?
+        .into_iter()
+        .map(|package| package.package_id)
+        .collect())
+}
+
+/// How a list of requested packages maps onto the configured repositories.
+#[derive(Debug, Clone, Default)]
+pub struct ListResolution {
+    /// Repository packages to install, as PackageKit ids.
+    pub install: Vec<String>,
+    /// Requested packages that are already installed at the requested version.
+    pub installed: Vec<String>,
+    /// Requested packages only available at another version, as `name version`, which
+    /// are installed at the newest version available instead.
+    pub substituted: Vec<String>,
+    /// Requested packages the configured repositories don't offer.
+    pub missing: Vec<String>,
+    /// Sources of [`ListResolution::install`], keyed by PackageKit id.
+    pub origins: HashMap<String, Origin>,
+    /// Packages to install, or their dependencies, that aren't signed by a trusted key.
+    pub unauthenticated: Vec<String>,
+    /// Why checking the repository signatures failed, in which case the packages may be
+    /// unauthenticated.
+    pub unauthenticated_error: Option<String>,
+}
+
+/// Resolves `(name, version)` pairs against the configured repositories.
+pub fn resolve_list(requested: Vec<(String, String)>) -> anyhow::Result<ListResolution> {
+    let names: Vec<&str> = requested.iter().map(|(name, _)| name.as_str()).collect();
+
+    let proxy = PackageKit::new()?;
+    let installed = proxy.installed_versions(&names)?;
+    let filter = Filter::NotInstalled as u64 | Filter::Arch as u64;
+    let available = proxy.resolve(&names, filter)?;
+
+    let mut resolution = ListResolution::default();
+    for (name, requested_version) in &requested {
+        if installed.get(name) == Some(requested_version) {
+            resolution.installed.push(name.clone());
+            continue;
+        }
+
+        let candidates = available.iter().filter(|package| package.name() == name);
+        if let Some(exact) = candidates
+            .clone()
+            .find(|package| package.version() == requested_version)
+        {
+            resolution.install.push(exact.package_id.clone());
+        } else if let Some(newest) =
+            candidates.max_by(|a, b| version::compare(a.version(), b.version()))
+        {
+            resolution.install.push(newest.package_id.clone());
+            resolution
+                .substituted
+                .push(format!("{name} {}", newest.version()));
+        } else {
+            resolution.missing.push(name.clone());
+        }
+    }
+    resolution.origins = sources::origins(&resolution.install);
+    let install: Vec<&str> = resolution.install.iter().map(String::as_str).collect();
+    match proxy.untrusted_packages(&install) {
+        Ok(untrusted) => resolution.unauthenticated = untrusted,
+        Err(why) => {
+            eprintln!("failed to check the repository signatures: {why}");
+            resolution.unauthenticated_error = Some(why.to_string());
+        }
+    }
+
+    Ok(resolution)
+}
+
+/// Lists the packages among `package_ids`, and their dependencies, that aren't signed by
+/// a trusted repository.
+pub fn untrusted_packages(package_ids: &[String]) -> anyhow::Result<Vec<String>> {
+    if package_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let ids: Vec<&str> = package_ids.iter().map(String::as_str).collect();
+    PackageKit::new()?.untrusted_packages(&ids)
+}
+
+/// Installs packages from the configured repositories by their PackageKit ids, including
+/// packages from unauthenticated repositories when `allow_untrusted` is set.
+pub fn install_repository_packages(
+    package_ids: Vec<String>,
+    allow_untrusted: bool,
+    f: Box<dyn FnMut(ItemProgress) + 'static>,
+) -> anyhow::Result<Vec<String>> {
+    packagekit::reset_cancel();
+    let ids: Vec<&str> = package_ids.iter().map(String::as_str).collect();
+
+    let proxy = PackageKit::new()?;
+    let options = InstallOptions {
+        allow_untrusted,
+        ..InstallOptions::default()
+    };
+    proxy.install_packages(&ids, options, f)?;
+
+    Ok(package_ids)
+}
+
+/// Installs the build dependencies of a source package from the configured repositories.
+///
+/// Only the first alternative of every dependency is considered, and dependencies which
+/// are already installed are skipped. Returns the ids of the installed packages.
+pub fn install_build_depends(
+    build_depends: Vec<Vec<Relation>>,
+    f: Box<dyn FnMut(ItemProgress) + 'static>,
+) -> anyhow::Result<Vec<String>> {
+    packagekit::reset_cancel();
+    let names: Vec<&str> = build_depends
+        .iter()
+        .filter_map(|group| group.first())
+        .map(|relation| relation.name.as_str())
+        .collect();
+
+    let proxy = PackageKit::new()?;
+    let filter = Filter::NotInstalled as u64 | Filter::Arch as u64 | Filter::Newest as u64;
+    let packages = proxy.resolve(&names, filter)?;
+
+    let package_ids: Vec<&str> = packages
+        .iter()
+        .map(|package| package.package_id.as_str())
+        .collect();
+
+    if !package_ids.is_empty() {
+        proxy.install_packages(&package_ids, InstallOptions::default(), f)?;
+    }
+
+    Ok(packages
+        .into_iter()
+        .map(|package| package.package_id)
+        .collect())
 }
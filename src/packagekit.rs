@@ -1,12 +1,19 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use anyhow::bail;
 
-use anyhow::anyhow;
 use packagekit_zbus::{
     zbus::{blocking::Connection, zvariant},
     PackageKit::PackageKitProxyBlocking,
     Transaction::TransactionProxyBlocking,
 };
 
+use crate::debconf;
+
 #[derive(Debug)]
 pub struct TransactionDetails {
     pub package_id: String,
@@ -15,11 +22,153 @@ pub struct TransactionDetails {
     pub url: String,
     pub license: String,
     pub size: String,
+    pub installed_size: u64,
+}
+
+#[derive(Debug)]
+pub struct TransactionPackage {
+    pub info: u32,
+    pub package_id: String,
+    pub summary: String,
+}
+
+impl TransactionPackage {
+    /// The package name, the first component of the package id.
+    pub fn name(&self) -> &str {
+        self.package_id.split(';').next().unwrap_or_default()
+    }
+
+    /// The package version, the second component of the package id.
+    pub fn version(&self) -> &str {
+        self.package_id.split(';').nth(1).unwrap_or_default()
+    }
+
+    /// Whether the transaction would remove this package.
+    pub fn is_removal(&self) -> bool {
+        self.info == Info::Removing as u32 || self.info == Info::Obsoleting as u32
+    }
+
+    /// Whether the transaction would change this already installed package, by removing,
+    /// upgrading or downgrading it.
+    pub fn is_change(&self) -> bool {
+        self.is_removal()
+            || self.info == Info::Updating as u32
+            || self.info == Info::Downgrading as u32
+    }
+}
+
+/// Progress reported by a transaction for one of its packages.
+#[derive(Debug, Clone)]
+pub struct ItemProgress {
+    pub package_id: String,
+    pub status: u32,
+    /// Progress of this package.
+    pub percentage: u32,
+    /// Progress of the whole transaction, above 100 when unknown.
+    pub total_percentage: u32,
+}
+
+impl ItemProgress {
+    /// The package name, the first component of the package id.
+    pub fn name(&self) -> &str {
+        self.package_id.split(';').next().unwrap_or_default()
+    }
+
+    /// Whether the transaction is queued behind other operations.
+    pub fn is_queued(&self) -> bool {
+        self.status == Status::Wait as u32 || self.status == Status::WaitingForLock as u32
+    }
+
+    /// Whether the transaction is waiting for the user to authorize it.
+    pub fn is_waiting_for_auth(&self) -> bool {
+        self.status == Status::WaitingForAuth as u32
+    }
+}
+
+/// An error reported by a transaction through its `ErrorCode` signal.
+#[derive(Debug)]
+pub struct TransactionError {
+    pub code: u32,
+    pub details: String,
+}
+
+impl TransactionError {
+    /// Whether Polkit refused to authorize the transaction.
+    pub fn is_not_authorized(&self) -> bool {
+        self.code == ErrorCode::NotAuthorized as u32
+    }
+
+    /// Whether another package manager held the lock.
+    pub fn is_lock_error(&self) -> bool {
+        self.code == ErrorCode::CannotGetLock as u32
+    }
+
+    /// Whether the transaction was cancelled, usually by [`cancel`].
+    pub fn is_cancelled(&self) -> bool {
+        self.code == ErrorCode::TransactionCancelled as u32
+    }
+
+    /// Whether the packages aren't signed by a trusted repository.
+    pub fn is_untrusted(&self) -> bool {
+        self.code == ErrorCode::MissingGpgSignature as u32
+            || self.code == ErrorCode::BadGpgSignature as u32
+            || self.code == ErrorCode::CannotInstallRepoUnsigned as u32
+    }
+
+    /// The error of a transaction that finished with `exit` without emitting `ErrorCode`.
+    fn from_exit(exit: u32) -> Self {
+        let (code, details) = match exit {
+            x if x == Exit::Cancelled as u32 || x == Exit::CancelledPriority as u32 => (
+                ErrorCode::TransactionCancelled,
+                "the transaction was cancelled",
+            ),
+            x if x == Exit::Killed as u32 => (
+                ErrorCode::Unknown,
+                "the transaction was killed by the daemon",
+            ),
+            x if x == Exit::KeyRequired as u32 => {
+                (ErrorCode::Unknown, "a signing key must be imported first")
+            }
+            x if x == Exit::EulaRequired as u32 => (
+                ErrorCode::Unknown,
+                "a license agreement must be accepted first",
+            ),
+            x if x == Exit::NeedUntrusted as u32 => (
+                ErrorCode::MissingGpgSignature,
+                "the packages aren't signed by a trusted repository",
+            ),
+            x if x == Exit::RepairRequired as u32 => (
+                ErrorCode::Unknown,
+                "the package database needs to be repaired first",
+            ),
+            _ => (ErrorCode::Unknown, "the transaction failed"),
+        };
+
+        Self {
+            code: code as u32,
+            details: format!("{details} (exit code {exit})"),
+        }
+    }
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (error code {})", self.details, self.code)
+    }
+}
+
+impl std::error::Error for TransactionError {}
+
+#[derive(Debug, Default)]
+pub struct TransactionResults {
+    pub details: Vec<TransactionDetails>,
+    pub packages: Vec<TransactionPackage>,
 }
 
 // https://github.com/PackageKit/PackageKit/blob/209aa62950e503494716fd046f8f5cb546bf57d4/lib/packagekit-glib2/pk-enum.h#L776-L798
 #[allow(dead_code)]
 #[repr(u64)]
+#[derive(Clone, Copy)]
 enum TransactionFlag {
     None = 1 << 0,
     OnlyTrusted = 1 << 1,
@@ -31,6 +180,111 @@ enum TransactionFlag {
     Last = 1 << 7,
 }
 
+// https://github.com/PackageKit/PackageKit/blob/209aa62950e503494716fd046f8f5cb546bf57d4/lib/packagekit-glib2/pk-enum.h#L573-L607
+#[allow(dead_code)]
+#[repr(u32)]
+enum Info {
+    Installed = 1,
+    Available = 2,
+    Updating = 11,
+    Installing = 12,
+    Removing = 13,
+    Obsoleting = 15,
+    Reinstalling = 19,
+    Downgrading = 20,
+    Untrusted = 23,
+}
+
+// https://github.com/PackageKit/PackageKit/blob/209aa62950e503494716fd046f8f5cb546bf57d4/lib/packagekit-glib2/pk-enum.h#L161-L194
+#[allow(dead_code)]
+#[repr(u64)]
+pub enum Filter {
+    None = 1 << 1,
+    Installed = 1 << 2,
+    NotInstalled = 1 << 3,
+    Newest = 1 << 16,
+    Arch = 1 << 18,
+}
+
+// https://github.com/PackageKit/PackageKit/blob/209aa62950e503494716fd046f8f5cb546bf57d4/lib/packagekit-glib2/pk-enum.h
+#[allow(dead_code)]
+#[repr(u32)]
+pub enum Status {
+    Wait = 1,
+    WaitingForLock = 30,
+    WaitingForAuth = 31,
+}
+
+// https://github.com/PackageKit/PackageKit/blob/209aa62950e503494716fd046f8f5cb546bf57d4/lib/packagekit-glib2/pk-enum.h
+#[allow(dead_code)]
+#[repr(u32)]
+enum ErrorCode {
+    Unknown = 0,
+    TransactionCancelled = 17,
+    CannotGetLock = 26,
+    BadGpgSignature = 30,
+    MissingGpgSignature = 31,
+    InvalidPackageFile = 38,
+    PackageCorrupt = 40,
+    NoSpaceOnDevice = 46,
+    NotAuthorized = 48,
+    CannotInstallRepoUnsigned = 50,
+}
+
+// https://github.com/PackageKit/PackageKit/blob/209aa62950e503494716fd046f8f5cb546bf57d4/lib/packagekit-glib2/pk-enum.h#L331-L346
+#[allow(dead_code)]
+#[repr(u32)]
+enum Exit {
+    Unknown = 0,
+    Success = 1,
+    Failed = 2,
+    Cancelled = 3,
+    KeyRequired = 4,
+    EulaRequired = 5,
+    Killed = 6,
+    MediaChangeRequired = 7,
+    NeedUntrusted = 8,
+    CancelledPriority = 9,
+    SkipTransaction = 10,
+    RepairRequired = 11,
+}
+
+/// Options for installing packages.
+#[derive(Debug, Clone, Default)]
+pub struct InstallOptions {
+    /// Allow installing packages that are not signed by a trusted repository.
+    pub allow_untrusted: bool,
+    /// Allow reinstalling packages already installed at the same version.
+    pub allow_reinstall: bool,
+    /// Allow replacing installed packages with older versions.
+    pub allow_downgrade: bool,
+    /// Alternative root directory to install into instead of the system root.
+    pub root: Option<PathBuf>,
+    /// Install even if dependencies aren't satisfied, which only dpkg can do.
+    pub ignore_dependencies: bool,
+}
+
+impl InstallOptions {
+    fn flags(&self) -> u64 {
+        let mut flags = 0;
+        if !self.allow_untrusted {
+            flags |= TransactionFlag::OnlyTrusted as u64;
+        }
+        if self.allow_reinstall {
+            flags |= TransactionFlag::AllowReinstall as u64;
+        }
+        if self.allow_downgrade {
+            flags |= TransactionFlag::AllowDowngrade as u64;
+        }
+
+        if flags == 0 {
+            TransactionFlag::None as u64
+        } else {
+            flags
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct PackageKit {
     connection: Connection,
@@ -47,6 +301,11 @@ impl PackageKit {
         Ok(PackageKitProxyBlocking::new(&self.connection)?)
     }
 
+    /// Whether other transactions are running, in which case new ones are queued.
+    pub fn is_busy(&self) -> anyhow::Result<bool> {
+        Ok(!self._proxy()?.get_transaction_list()?.is_empty())
+    }
+
     pub fn transaction(&self) -> anyhow::Result<TransactionProxyBlocking> {
         let pk = PackageKitProxyBlocking::new(&self.connection)?;
         let tx_path = pk.create_transaction()?;
@@ -58,28 +317,329 @@ impl PackageKit {
         Ok(tx)
     }
 
+    /// Installs local package files in a single transaction.
+    ///
+    /// Local files carry no repository signature, so the transaction is only allowed to
+    /// install unauthenticated packages when `options.allow_untrusted` is set, which
+    /// callers tie to the user acknowledging that they trust the files.
     pub fn install_packages_files(
         &self,
         files: &[&str],
-        mut f: Box<dyn FnMut(u32) + 'static>,
+        options: InstallOptions,
+        mut f: Box<dyn FnMut(ItemProgress) + 'static>,
     ) -> anyhow::Result<()> {
+        if options.ignore_dependencies {
+            bail!("PackageKit can't install packages while ignoring their dependencies");
+        }
+
+        // PackageKit transactions always act on the root the daemon was started for.
+        if let Some(root) = &options.root {
+            bail!(
+                "PackageKit can't install into alternative roots such as {}",
+                root.display()
+            );
+        }
+
         let tx = self.transaction()?;
         tx.set_hints(&["interactive=true"])?;
         tx.set_hints(&["supports-plural-signals=true"])?;
-        println!("installing packages {:?}", files);
-        tx.install_files(TransactionFlag::None as u64, &files)?;
-        let _tx_packages = transaction_handle(tx, |total_percentage| {
-            f(total_percentage);
+        set_frontend_socket(&tx)?;
+        if options.allow_untrusted {
+            println!("allowing unauthenticated packages {:?}", files);
+        }
+        println!("installing packages {:?} with {:?}", files, options);
+        tx.install_files(options.flags(), &files)?;
+        let _running = Running::register(&tx);
+        let _tx_packages = transaction_handle(tx, |progress| {
+            f(progress);
         })?;
         Ok(())
     }
+
+    /// Simulates installing local files, returning the packages the transaction would touch.
+    pub fn simulate_install_files(
+        &self,
+        files: &[&str],
+    ) -> anyhow::Result<Vec<TransactionPackage>> {
+        let tx = self.transaction()?;
+        tx.install_files(TransactionFlag::Simulate as u64, files)?;
+        Ok(transaction_handle(tx, |_| {})?.packages)
+    }
+
+    /// Simulates installing packages from the configured repositories, returning the
+    /// packages the transaction would touch.
+    pub fn simulate_install_packages(
+        &self,
+        package_ids: &[&str],
+    ) -> anyhow::Result<Vec<TransactionPackage>> {
+        let tx = self.transaction()?;
+        let flags = TransactionFlag::Simulate as u64 | TransactionFlag::AllowDowngrade as u64;
+        tx.install_packages(flags, package_ids)?;
+        Ok(transaction_handle(tx, |_| {})?.packages)
+    }
+
+    /// Installs packages from the configured repositories in a single transaction.
+    ///
+    /// Packages from repositories without a trusted signature are refused unless
+    /// `options.allow_untrusted` is set, which callers tie to the user acknowledging the
+    /// risk. Alternative roots and ignoring dependencies aren't supported.
+    pub fn install_packages(
+        &self,
+        package_ids: &[&str],
+        options: InstallOptions,
+        mut f: Box<dyn FnMut(ItemProgress) + 'static>,
+    ) -> anyhow::Result<()> {
+        let tx = self.transaction()?;
+        tx.set_hints(&["interactive=true"])?;
+        tx.set_hints(&["supports-plural-signals=true"])?;
+        set_frontend_socket(&tx)?;
+        println!("installing packages {:?} with {:?}", package_ids, options);
+        tx.install_packages(options.flags(), package_ids)?;
+        let _running = Running::register(&tx);
+        let _tx_packages = transaction_handle(tx, |progress| {
+            f(progress);
+        })?;
+        Ok(())
+    }
+
+    /// Lists the packages among `package_ids`, and the dependencies they would pull in,
+    /// that aren't signed by a trusted repository.
+    ///
+    /// The install is simulated while only allowing trusted packages, which the backend
+    /// refuses when any package is untrusted.
+    pub fn untrusted_packages(&self, package_ids: &[&str]) -> anyhow::Result<Vec<String>> {
+        if package_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let tx = self.transaction()?;
+        let flags = TransactionFlag::OnlyTrusted as u64 | TransactionFlag::Simulate as u64;
+        tx.install_packages(flags, package_ids)?;
+        let (results, outcome) = transaction_run(tx, |_| {});
+
+        let mut untrusted: Vec<String> = results
+            .packages
+            .iter()
+            .filter(|package| package.info == Info::Untrusted as u32)
+            .map(|package| package.name().to_string())
+            .collect();
+
+        if let Err(why) = outcome {
+            let refused = why
+                .downcast_ref::<TransactionError>()
+                .is_some_and(TransactionError::is_untrusted);
+            if !refused {
+                return Err(why);
+            }
+
+            // Not every backend says which packages it refused.
+            if untrusted.is_empty() {
+                untrusted = package_ids
+                    .iter()
+                    .map(|package_id| package_id.split(';').next().unwrap_or_default().to_string())
+                    .collect();
+            }
+        }
+
+        untrusted.sort();
+        untrusted.dedup();
+        Ok(untrusted)
+    }
+
+    /// Downloads the package lists of the configured repositories again.
+    pub fn refresh_cache(&self) -> anyhow::Result<()> {
+        let tx = self.transaction()?;
+        tx.refresh_cache(false)?;
+        transaction_handle(tx, |_| {})?;
+        Ok(())
+    }
+
+    pub fn get_details_local(&self, files: &[&str]) -> anyhow::Result<Vec<TransactionDetails>> {
+        let tx = self.transaction()?;
+        tx.get_details_local(files)?;
+        Ok(transaction_handle(tx, |_| {})?.details)
+    }
+
+    /// Returns the installed version of a package, if it is installed.
+    ///
+    /// Packages of other architectures are ignored, except for `all`.
+    pub fn installed_version(
+        &self,
+        name: &str,
+        architecture: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let packages = self.resolve(&[name], Filter::Installed as u64)?;
+
+        Ok(packages.iter().find_map(|package| {
+            let mut parts = package.package_id.split(';');
+            let (_name, version, arch) = (parts.next()?, parts.next()?, parts.next()?);
+            (arch == architecture || arch == "all" || architecture == "all")
+                .then(|| version.to_string())
+        }))
+    }
+
+    /// Returns the installed versions of the given packages, keyed by package name.
+    pub fn installed_versions(&self, names: &[&str]) -> anyhow::Result<HashMap<String, String>> {
+        if names.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let packages = self.resolve(names, Filter::Installed as u64)?;
+
+        Ok(packages
+            .iter()
+            .filter_map(|package| {
+                let mut parts = package.package_id.split(';');
+                Some((parts.next()?.to_string(), parts.next()?.to_string()))
+            })
+            .collect())
+    }
+
+    /// Returns the ids of every version of a package the configured repositories offer,
+    /// leaving out the installed version and `local_version`, which the local file
+    /// already provides.
+    pub fn repository_versions(
+        &self,
+        name: &str,
+        architecture: &str,
+        local_version: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let packages = self.resolve(&[name], Filter::None as u64)?;
+
+        Ok(packages
+            .into_iter()
+            .filter(|package| {
+                let mut parts = package.package_id.split(';').skip(2);
+                let (arch, data) = (
+                    parts.next().unwrap_or_default(),
+                    parts.next().unwrap_or_default(),
+                );
+                // Repository packages carry their origin as data, unlike local files and
+                // installed packages.
+                (arch == architecture || arch == "all")
+                    && !data.is_empty()
+                    && !data.starts_with("local")
+                    && !data.starts_with("installed")
+                    && package.version() != local_version
+            })
+            .map(|package| package.package_id)
+            .collect())
+    }
+
+    /// Resolves package names into the packages known to the backend.
+    pub fn resolve(&self, names: &[&str], filter: u64) -> anyhow::Result<Vec<TransactionPackage>> {
+        let tx = self.transaction()?;
+        tx.resolve(filter, names)?;
+        Ok(transaction_handle(tx, |_| {})?.packages)
+    }
+}
+
+/// Object path of the install transaction that is running, for [`cancel`].
+static RUNNING: Mutex<Option<String>> = Mutex::new(None);
+
+/// Set by [`cancel`], so that batch installs don't start their next transaction.
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Registers an install transaction as the running one for as long as it's alive.
+struct Running;
+
+impl Running {
+    fn register(tx: &TransactionProxyBlocking) -> Self {
+        *RUNNING.lock().unwrap() = Some(tx.path().to_string());
+        Self
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        RUNNING.lock().unwrap().take();
+    }
+}
+
+/// Cancels the running install transaction, and the rest of its batch.
+pub fn cancel() -> anyhow::Result<()> {
+    CANCELLED.store(true, Ordering::SeqCst);
+
+    let Some(path) = RUNNING.lock().unwrap().clone() else {
+        return Ok(());
+    };
+    let connection = Connection::system()?;
+    let tx = TransactionProxyBlocking::builder(&connection)
+        .destination("org.freedesktop.PackageKit")?
+        .path(path)?
+        .build()?;
+    println!("cancelling transaction {}", tx.path());
+    tx.cancel()?;
+
+    Ok(())
+}
+
+/// Whether the user cancelled since the current install job started.
+pub fn cancel_requested() -> bool {
+    CANCELLED.load(Ordering::SeqCst)
+}
+
+/// Forgets an earlier cancellation, once a new install job starts.
+pub fn reset_cancel() {
+    CANCELLED.store(false, Ordering::SeqCst);
+}
+
+/// Points the transaction's debconf at Wizard's frontend, if it's listening.
+fn set_frontend_socket(tx: &TransactionProxyBlocking) -> anyhow::Result<()> {
+    let socket = debconf::socket_path();
+    if socket.exists() {
+        tx.set_hints(&[&format!("frontend-socket={}", socket.display())])?;
+    }
+    Ok(())
+}
+
+/// Formats a size in bytes using decimal units, as PackageKit reports them.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["kB", "MB", "GB", "TB"];
+
+    if bytes < 1000 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1000.0;
+    let mut unit = 0;
+    while size >= 1000.0 && unit < UNITS.len() - 1 {
+        size /= 1000.0;
+        unit += 1;
+    }
+
+    if size < 10.0 {
+        format!("{size:.1} {}", UNITS[unit])
+    } else {
+        format!("{size:.0} {}", UNITS[unit])
+    }
 }
 
 pub fn transaction_handle(
     tx: TransactionProxyBlocking,
-    mut on_progress: impl FnMut(u32),
-) -> anyhow::Result<Vec<TransactionDetails>> {
-    let mut details = Vec::new();
+    on_progress: impl FnMut(ItemProgress),
+) -> anyhow::Result<TransactionResults> {
+    let (results, outcome) = transaction_run(tx, on_progress);
+    outcome.map(|()| results)
+}
+
+/// Follows a transaction until it finishes, returning what it reported along with its
+/// outcome, so that the reports of failed transactions can be inspected too.
+fn transaction_run(
+    tx: TransactionProxyBlocking,
+    on_progress: impl FnMut(ItemProgress),
+) -> (TransactionResults, anyhow::Result<()>) {
+    let mut results = TransactionResults::default();
+    let outcome = transaction_signals(&tx, &mut results, on_progress);
+    (results, outcome)
+}
+
+fn transaction_signals(
+    tx: &TransactionProxyBlocking,
+    results: &mut TransactionResults,
+    mut on_progress: impl FnMut(ItemProgress),
+) -> anyhow::Result<()> {
+    let mut error = None;
 
     for signal in tx.receive_all_signals()? {
         if let Some(member) = signal.member() {
@@ -99,13 +659,11 @@ pub fn transaction_handle(
                             }
                         }
                     };
-                    let size = match map.get("size") {
-                        Some(zvariant::Value::U64(number)) => {
-                            let size_in_mb = number / 1_000_000;
-                            format!("{} MB", size_in_mb)
-                        }
-                        _ => String::from("0 MB"),
+                    let installed_size = match map.get("size") {
+                        Some(zvariant::Value::U64(number)) => *number,
+                        _ => 0,
                     };
+                    let size = format_size(installed_size);
 
                     let Some(package_id) = get_string("package-id") else {
                         continue;
@@ -115,35 +673,50 @@ pub fn transaction_handle(
                     let url = get_string("url").unwrap_or_default();
                     let license = get_string("license").unwrap_or_default();
 
-                    details.push(TransactionDetails {
+                    results.details.push(TransactionDetails {
                         package_id,
                         summary,
                         description,
                         url,
                         license,
                         size,
+                        installed_size,
                     });
                 }
                 "ErrorCode" => {
                     // https://www.freedesktop.org/software/PackageKit/gtk-doc/Transaction.html#Transaction::ErrorCode
+                    // The daemon still emits `Finished` afterwards, with the exit code that
+                    // decides the outcome.
                     let (code, details) = signal.body::<(u32, String)>()?;
-                    return Err(anyhow!("{details} (error code {code})"));
+                    error = Some(TransactionError { code, details });
                 }
                 "ItemProgress" => {
                     // https://www.freedesktop.org/software/PackageKit/gtk-doc/Transaction.html#Transaction::ItemProgress
                     let (package_id, status, percentage) = signal.body::<(String, u32, u32)>()?;
                     println!("Status {status} {} {percentage}", package_id);
                     let total_percentage = tx.percentage().unwrap_or(percentage);
-                    on_progress(total_percentage)
+                    on_progress(ItemProgress {
+                        package_id,
+                        status,
+                        percentage,
+                        total_percentage,
+                    })
                 }
                 "Package" => {
                     // https://www.freedesktop.org/software/PackageKit/gtk-doc/Transaction.html#Transaction::Package
-                    let (info, package_id, _summary) = signal.body::<(u32, String, String)>()?;
+                    let (info, package_id, summary) = signal.body::<(u32, String, String)>()?;
 
                     println!("Info {info} {}", package_id);
+                    results.packages.push(TransactionPackage {
+                        info,
+                        package_id,
+                        summary,
+                    });
                 }
                 "Finished" => {
-                    break;
+                    // https://www.freedesktop.org/software/PackageKit/gtk-doc/Transaction.html#Transaction::Finished
+                    let (exit, _runtime) = signal.body::<(u32, u32)>()?;
+                    return transaction_outcome(exit, error).map_err(Into::into);
                 }
                 _ => {
                     println!("unknown signal {}", member);
@@ -151,5 +724,93 @@ pub fn transaction_handle(
             }
         }
     }
-    Ok(details)
+
+    // The signal stream ended early, such as when the daemon exited mid-transaction, so
+    // the transaction can't be assumed to have succeeded.
+    Err(error
+        .unwrap_or_else(|| TransactionError::from_exit(Exit::Unknown as u32))
+        .into())
+}
+
+/// Decides the outcome of a transaction from its `Finished` exit code and the last error it
+/// reported through `ErrorCode`, if any.
+fn transaction_outcome(exit: u32, error: Option<TransactionError>) -> Result<(), TransactionError> {
+    if exit == Exit::Success as u32 {
+        if let Some(error) = error {
+            eprintln!("transaction succeeded despite reporting an error: {error}");
+        }
+        return Ok(());
+    }
+
+    let mut error = error.unwrap_or_else(|| TransactionError::from_exit(exit));
+    // Cancelling can surface as another error from the backend.
+    if exit == Exit::Cancelled as u32 || exit == Exit::CancelledPriority as u32 {
+        error.code = ErrorCode::TransactionCancelled as u32;
+    }
+    Err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(code: ErrorCode) -> TransactionError {
+        TransactionError {
+            code: code as u32,
+            details: String::from("details"),
+        }
+    }
+
+    #[test]
+    fn success_is_ok() {
+        assert!(transaction_outcome(Exit::Success as u32, None).is_ok());
+    }
+
+    #[test]
+    fn error_then_success_is_ok() {
+        let outcome = transaction_outcome(Exit::Success as u32, Some(error(ErrorCode::Unknown)));
+        assert!(outcome.is_ok());
+    }
+
+    #[test]
+    fn failed_keeps_reported_error() {
+        let outcome =
+            transaction_outcome(Exit::Failed as u32, Some(error(ErrorCode::CannotGetLock)));
+        assert!(outcome.unwrap_err().is_lock_error());
+    }
+
+    #[test]
+    fn failed_without_error_is_err() {
+        let error = transaction_outcome(Exit::Failed as u32, None).unwrap_err();
+        assert_eq!(error.code, ErrorCode::Unknown as u32);
+        assert!(!error.is_cancelled());
+    }
+
+    #[test]
+    fn cancelled_is_cancelled() {
+        let error = transaction_outcome(Exit::Cancelled as u32, None).unwrap_err();
+        assert!(error.is_cancelled());
+    }
+
+    #[test]
+    fn cancelled_priority_overrides_backend_error() {
+        let outcome = transaction_outcome(
+            Exit::CancelledPriority as u32,
+            Some(error(ErrorCode::Unknown)),
+        );
+        assert!(outcome.unwrap_err().is_cancelled());
+    }
+
+    #[test]
+    fn need_untrusted_is_untrusted() {
+        let error = transaction_outcome(Exit::NeedUntrusted as u32, None).unwrap_err();
+        assert!(error.is_untrusted());
+    }
+
+    #[test]
+    fn killed_is_err() {
+        let error = transaction_outcome(Exit::Killed as u32, None).unwrap_err();
+        assert!(!error.is_cancelled());
+        assert!(error.details.contains("killed"));
+    }
 }
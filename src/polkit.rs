@@ -0,0 +1,73 @@
+//! Explains why PackageKit refused to authorize a transaction.
+
+use std::collections::HashMap;
+
+use zbus::blocking::Connection;
+use zbus_polkit::policykit1::{AuthorityProxyBlocking, Subject};
+
+use crate::fl;
+use crate::packagekit::TransactionError;
+
+/// Action checked by PackageKit before installing trusted packages.
+pub const INSTALL_ACTION: &str = "org.freedesktop.packagekit.package-install";
+/// Action checked by PackageKit before installing unsigned local files.
+pub const INSTALL_UNTRUSTED_ACTION: &str = "org.freedesktop.packagekit.package-install-untrusted";
+
+/// The action PackageKit checks before installing local files.
+pub fn install_action(allow_untrusted: bool) -> &'static str {
+    if allow_untrusted {
+        INSTALL_UNTRUSTED_ACTION
+    } else {
+        INSTALL_ACTION
+    }
+}
+
+/// Why an authorization was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Denial {
+    /// The user could have authorized, but dismissed the prompt.
+    Cancelled,
+    /// The system policy doesn't let the user perform the action at all.
+    NotPermitted,
+}
+
+/// Asks Polkit, without prompting, whether the user could have performed `action_id`.
+fn check_denial(action_id: &str) -> anyhow::Result<Denial> {
+    let connection = Connection::system()?;
+    let authority = AuthorityProxyBlocking::new(&connection)?;
+    let subject = Subject::new_for_owner(std::process::id(), None, None)?;
+
+    let result = authority.check_authorization(
+        &subject,
+        action_id,
+        &HashMap::new(),
+        Default::default(),
+        "",
+    )?;
+
+    if result.is_authorized || result.is_challenge {
+        Ok(Denial::Cancelled)
+    } else {
+        Ok(Denial::NotPermitted)
+    }
+}
+
+/// Describes a failed transaction, telling a dismissed authorization prompt apart from a
+/// policy that forbids installing packages.
+pub fn describe_failure(why: &anyhow::Error, action_id: &str) -> String {
+    let not_authorized = why
+        .downcast_ref::<TransactionError>()
+        .is_some_and(TransactionError::is_not_authorized);
+    if !not_authorized {
+        return why.to_string();
+    }
+
+    match check_denial(action_id) {
+        Ok(Denial::Cancelled) => fl!("authorization-cancelled"),
+        Ok(Denial::NotPermitted) => fl!("authorization-not-permitted"),
+        Err(polkit_why) => {
+            eprintln!("failed to check authorization: {polkit_why}");
+            why.to_string()
+        }
+    }
+}
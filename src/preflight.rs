@@ -0,0 +1,547 @@
+//! Checks run before installing packages, so surprises are raised before the transaction.
+
+use std::path::PathBuf;
+
+use nix::sys::statvfs::statvfs;
+
+use crate::fl;
+use crate::package::{
+    can_enable_architecture, essential_packages, held_packages, simulate_install,
+    supported_architectures, untrusted_packages, Package,
+};
+use crate::packagekit::{format_size, PackageKit};
+
+/// The filesystem packages are installed into without an alternative root.
+const SYSTEM_ROOT: &str = "/";
+
+/// The outcome of one check of the checklist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    /// The install may proceed once the user acknowledged the problem.
+    Warn,
+    /// The install mustn't proceed.
+    Fail,
+    /// The check itself failed, so the user must acknowledge proceeding without it.
+    Unknown,
+}
+
+/// One row of the pre-flight checklist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub label: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(label: String, status: CheckStatus, detail: String) -> Self {
+        Self {
+            label,
+            status,
+            detail,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Preflight {
+    /// Selected files that aren't valid packages, and are left out of the install.
+    pub invalid_files: Vec<String>,
+    /// Packages built for an architecture dpkg isn't configured for, as `name (arch)`.
+    pub incompatible: Vec<String>,
+    /// Foreign architectures of the incompatible packages that dpkg could be configured
+    /// for, such as `i386` on `amd64`.
+    pub enableable_architectures: Vec<String>,
+    /// Why listing the architectures dpkg supports failed, in which case the packages
+    /// weren't checked against them.
+    pub architecture_error: Option<String>,
+    /// Whether PackageKit answered, without which nothing can be installed.
+    pub daemon_available: bool,
+    /// Installed packages that the transaction would remove.
+    pub removals: Vec<String>,
+    /// Why simulating the install failed, in which case the removals are unknown.
+    pub simulation_error: Option<String>,
+    /// Essential or protected packages among the removals, which block the install.
+    pub essential_removals: Vec<String>,
+    /// Why checking the removals for essential packages failed, which blocks the install
+    /// too.
+    pub essential_error: Option<String>,
+    /// Packages on hold that the transaction would remove, upgrade or downgrade anyway.
+    pub held_changes: Vec<String>,
+    /// Why listing the held packages failed, in which case held packages may be changed.
+    pub held_error: Option<String>,
+    /// Total installed size of the packages, in bytes.
+    pub installed_size: u64,
+    /// Whether the size of any package was estimated from its data archive.
+    pub size_estimated: bool,
+    /// Space available on the install root's filesystem, in bytes, if it could be determined.
+    pub free_space: Option<u64>,
+    /// Whether the installed size exceeds the configured warning threshold.
+    pub large: bool,
+    /// Number of packages installed from a chosen repository version.
+    pub repository_packages: usize,
+    /// Repository packages, or their dependencies, that aren't signed by a trusted key.
+    pub unauthenticated: Vec<String>,
+    /// Why checking the repository signatures failed, in which case the repository
+    /// packages may be unauthenticated.
+    pub unauthenticated_error: Option<String>,
+    /// Packages whose pre-dependencies aren't satisfied, which is riskier to install.
+    pub unsatisfied_pre_depends: Vec<String>,
+    /// The alternative root to install into, if one is configured.
+    pub install_root: Option<PathBuf>,
+    /// Whether the install root isn't an existing directory anymore.
+    pub install_root_missing: bool,
+    /// Whether the install goes through PackageKit, which can't use the install root.
+    pub install_root_unsupported: bool,
+}
+
+impl Preflight {
+    /// Runs every check against the binary packages in `packages`.
+    ///
+    /// `invalid_files` are the selected files that failed to load, and `size_threshold`
+    /// is the installed size, in bytes, above which the install is flagged as large.
+    /// `install_root` is the configured alternative root, which only dpkg can install into,
+    /// so only when `ignore_dependencies` is set.
+    pub fn run(
+        packages: Vec<Package>,
+        invalid_files: Vec<String>,
+        size_threshold: u64,
+        install_root: Option<PathBuf>,
+        ignore_dependencies: bool,
+    ) -> Self {
+        let daemon_available = match PackageKit::new().and_then(|proxy| proxy.is_busy()) {
+            Ok(_) => true,
+            Err(why) => {
+                eprintln!("PackageKit isn't available: {why}");
+                false
+            }
+        };
+
+        let mut architecture_error = None;
+        let (incompatible, enableable_architectures) = match supported_architectures() {
+            Ok(architectures) => {
+                let native = architectures
+                    .first()
+                    .map(String::as_str)
+                    .unwrap_or_default();
+                let mut incompatible = Vec::new();
+                let mut enableable = Vec::new();
+                for package in packages.iter().filter(|package| {
+                    !package.is_source()
+                        && package.architecture != "all"
+                        && !architectures.contains(&package.architecture)
+                }) {
+                    incompatible.push(format!("{} ({})", package.name, package.architecture));
+                    if can_enable_architecture(native, &package.architecture)
+                        && !enableable.contains(&package.architecture)
+                    {
+                        enableable.push(package.architecture.clone());
+                    }
+                }
+                (incompatible, enableable)
+            }
+            Err(why) => {
+                eprintln!("failed to list supported architectures: {why}");
+                architecture_error = Some(why.to_string());
+                (Vec::new(), Vec::new())
+            }
+        };
+
+        let installed_size = packages
+            .iter()
+            .filter(|package| !package.is_source())
+            .map(|package| package.installed_size)
+            .sum();
+
+        let size_estimated = packages
+            .iter()
+            .any(|package| !package.is_source() && package.installed_size_estimated);
+
+        let repository_ids: Vec<String> = packages
+            .iter()
+            .filter(|package| !package.is_source())
+            .filter_map(|package| package.repository_id.clone())
+            .collect();
+        let (unauthenticated, unauthenticated_error) = match untrusted_packages(&repository_ids) {
+            Ok(untrusted) => (untrusted, None),
+            Err(why) => {
+                eprintln!("failed to check the repository signatures: {why}");
+                (Vec::new(), Some(why.to_string()))
+            }
+        };
+
+        let unsatisfied_pre_depends = packages
+            .iter()
+            .filter(|package| !package.unsatisfied_pre_depends().is_empty())
+            .map(|package| package.name.clone())
+            .collect();
+
+        let (simulation, simulation_error) = match simulate_install(packages) {
+            Ok(simulation) => (simulation, None),
+            Err(why) => {
+                eprintln!("failed to simulate installation: {why}");
+                (Default::default(), Some(why.to_string()))
+            }
+        };
+        let removals = simulation.removals;
+        let (essential_removals, essential_error) = match essential_packages(&removals) {
+            Ok(essential) => (essential, None),
+            Err(why) => {
+                eprintln!("failed to check for essential packages: {why}");
+                (Vec::new(), Some(why.to_string()))
+            }
+        };
+
+        let (held, held_error) = match held_packages() {
+            Ok(held) => (held, None),
+            Err(why) => {
+                eprintln!("failed to list held packages: {why}");
+                (Vec::new(), Some(why.to_string()))
+            }
+        };
+        let held_changes = simulation
+            .changes
+            .into_iter()
+            .filter(|name| held.contains(name))
+            .collect();
+
+        let root = install_root
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(SYSTEM_ROOT));
+        let free_space = match statvfs(&root) {
+            Ok(stat) => {
+                // The field types differ between platforms.
+                #[allow(clippy::unnecessary_cast)]
+                let free_space = stat.blocks_available() as u64 * stat.fragment_size() as u64;
+                Some(free_space)
+            }
+            Err(why) => {
+                eprintln!("failed to read free space of {}: {why}", root.display());
+                None
+            }
+        };
+
+        Self {
+            invalid_files,
+            incompatible,
+            enableable_architectures,
+            architecture_error,
+            daemon_available,
+            removals,
+            simulation_error,
+            essential_removals,
+            essential_error,
+            held_changes,
+            held_error,
+            installed_size,
+            size_estimated,
+            free_space,
+            large: installed_size > size_threshold,
+            repository_packages: repository_ids.len(),
+            unauthenticated,
+            unauthenticated_error,
+            unsatisfied_pre_depends,
+            install_root_missing: install_root.as_ref().is_some_and(|root| !root.is_dir()),
+            install_root_unsupported: install_root.is_some() && !ignore_dependencies,
+            install_root,
+        }
+    }
+
+    /// A checklist on which every check passes, for tests to break one check of.
+    #[cfg(test)]
+    pub fn passing() -> Self {
+        Self {
+            daemon_available: true,
+            free_space: Some(u64::MAX),
+            ..Self::default()
+        }
+    }
+
+    /// The total installed size, marked as an estimate when it is one.
+    pub fn size_label(&self) -> String {
+        let size = format_size(self.installed_size);
+        if self.size_estimated {
+            fl!("estimated-size", size = size)
+        } else {
+            size
+        }
+    }
+
+    /// Whether the packages don't fit in the available space.
+    pub fn insufficient_space(&self) -> bool {
+        self.free_space
+            .is_some_and(|free_space| self.installed_size > free_space)
+    }
+
+    /// Whether the user must explicitly allow changing held packages before installing.
+    pub fn holds_need_override(&self) -> bool {
+        !self.held_changes.is_empty() || self.held_error.is_some()
+    }
+
+    /// Whether the install mustn't proceed at all.
+    pub fn blocked(&self) -> bool {
+        self.checks()
+            .iter()
+            .any(|check| check.status == CheckStatus::Fail)
+    }
+
+    /// Whether the user must be asked before the install proceeds.
+    pub fn needs_confirmation(&self) -> bool {
+        self.checks()
+            .iter()
+            .any(|check| check.status != CheckStatus::Pass)
+    }
+
+    /// The checklist shown before installing, one row per kind of check.
+    pub fn checks(&self) -> Vec<Check> {
+        let valid = if self.invalid_files.is_empty() {
+            Check::new(
+                fl!("check-valid"),
+                CheckStatus::Pass,
+                fl!("check-valid-pass"),
+            )
+        } else {
+            Check::new(
+                fl!("check-valid"),
+                CheckStatus::Warn,
+                fl!("check-valid-warn", files = self.invalid_files.join(", ")),
+            )
+        };
+
+        let architecture = if let Some(why) = &self.architecture_error {
+            Check::new(
+                fl!("check-architecture"),
+                CheckStatus::Unknown,
+                fl!("check-architecture-unknown", reason = why.clone()),
+            )
+        } else if self.incompatible.is_empty() {
+            Check::new(
+                fl!("check-architecture"),
+                CheckStatus::Pass,
+                fl!("check-architecture-pass"),
+            )
+        } else {
+            Check::new(
+                fl!("check-architecture"),
+                CheckStatus::Fail,
+                fl!(
+                    "check-architecture-fail",
+                    packages = self.incompatible.join(", ")
+                ),
+            )
+        };
+
+        let size = self.size_label();
+        let space = match self.free_space {
+            Some(free) if self.insufficient_space() => Check::new(
+                fl!("check-space"),
+                CheckStatus::Fail,
+                fl!("insufficient-space", size = size, free = format_size(free)),
+            ),
+            _ if self.large => Check::new(
+                fl!("check-space"),
+                CheckStatus::Warn,
+                fl!("large-install", size = size),
+            ),
+            Some(free) => Check::new(
+                fl!("check-space"),
+                CheckStatus::Pass,
+                fl!("check-space-pass", size = size, free = format_size(free)),
+            ),
+            None => Check::new(
+                fl!("check-space"),
+                CheckStatus::Unknown,
+                fl!("check-space-unknown", size = size),
+            ),
+        };
+
+        let conflicts = if !self.essential_removals.is_empty() {
+            Check::new(
+                fl!("check-conflicts"),
+                CheckStatus::Fail,
+                fl!(
+                    "essential-removals",
+                    packages = self.essential_removals.join(", ")
+                ),
+            )
+        } else if let Some(why) = &self.essential_error {
+            Check::new(
+                fl!("check-conflicts"),
+                CheckStatus::Fail,
+                fl!("check-essential-unknown", reason = why.clone()),
+            )
+        } else if let Some(why) = &self.simulation_error {
+            Check::new(
+                fl!("check-conflicts"),
+                CheckStatus::Unknown,
+                fl!("check-conflicts-unknown", reason = why.clone()),
+            )
+        } else if !self.removals.is_empty() {
+            Check::new(
+                fl!("check-conflicts"),
+                CheckStatus::Warn,
+                fl!(
+                    "check-conflicts-removals",
+                    packages = self.removals.join(", ")
+                ),
+            )
+        } else if !self.held_changes.is_empty() {
+            Check::new(
+                fl!("check-conflicts"),
+                CheckStatus::Warn,
+                fl!("held-changes", packages = self.held_changes.join(", ")),
+            )
+        } else if let Some(why) = &self.held_error {
+            Check::new(
+                fl!("check-conflicts"),
+                CheckStatus::Unknown,
+                fl!("check-holds-unknown", reason = why.clone()),
+            )
+        } else {
+            Check::new(
+                fl!("check-conflicts"),
+                CheckStatus::Pass,
+                fl!("check-conflicts-pass"),
+            )
+        };
+
+        let pre_depends = if self.unsatisfied_pre_depends.is_empty() {
+            Check::new(
+                fl!("check-pre-depends"),
+                CheckStatus::Pass,
+                fl!("check-pre-depends-pass"),
+            )
+        } else {
+            Check::new(
+                fl!("check-pre-depends"),
+                CheckStatus::Warn,
+                fl!(
+                    "pre-depends-risk-packages",
+                    packages = self.unsatisfied_pre_depends.join(", ")
+                ),
+            )
+        };
+
+        let daemon = if self.daemon_available {
+            Check::new(
+                fl!("check-daemon"),
+                CheckStatus::Pass,
+                fl!("check-daemon-pass"),
+            )
+        } else {
+            Check::new(
+                fl!("check-daemon"),
+                CheckStatus::Fail,
+                fl!("check-daemon-fail"),
+            )
+        };
+
+        let mut checks = vec![valid, architecture, space, conflicts, pre_depends, daemon];
+        if let Some(root) = &self.install_root {
+            let root = root.display().to_string();
+            checks.push(if self.install_root_missing {
+                Check::new(
+                    fl!("install-root"),
+                    CheckStatus::Fail,
+                    fl!("check-install-root-missing", root = root),
+                )
+            } else if self.install_root_unsupported {
+                Check::new(
+                    fl!("install-root"),
+                    CheckStatus::Fail,
+                    fl!("check-install-root-unsupported", root = root),
+                )
+            } else {
+                Check::new(
+                    fl!("install-root"),
+                    CheckStatus::Pass,
+                    fl!("check-install-root-pass", root = root),
+                )
+            });
+        }
+        if self.repository_packages > 0 {
+            checks.push(if let Some(why) = &self.unauthenticated_error {
+                Check::new(
+                    fl!("check-signatures"),
+                    CheckStatus::Unknown,
+                    fl!("check-signatures-unknown", reason = why.clone()),
+                )
+            } else if self.unauthenticated.is_empty() {
+                Check::new(
+                    fl!("check-signatures"),
+                    CheckStatus::Pass,
+                    fl!("check-signatures-pass"),
+                )
+            } else {
+                Check::new(
+                    fl!("check-signatures"),
+                    CheckStatus::Warn,
+                    fl!(
+                        "unauthenticated-packages",
+                        packages = self.unauthenticated.join(", ")
+                    ),
+                )
+            });
+        }
+        checks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conflicts(preflight: &Preflight) -> CheckStatus {
+        preflight.checks()[3].status
+    }
+
+    #[test]
+    fn passes_without_problems() {
+        let preflight = Preflight::passing();
+        assert_eq!(conflicts(&preflight), CheckStatus::Pass);
+        assert!(!preflight.needs_confirmation());
+    }
+
+    #[test]
+    fn failed_simulation_is_unknown() {
+        let preflight = Preflight {
+            simulation_error: Some(String::from("daemon exited")),
+            ..Preflight::passing()
+        };
+        assert_eq!(conflicts(&preflight), CheckStatus::Unknown);
+        assert!(preflight.needs_confirmation());
+    }
+
+    #[test]
+    fn failed_held_lookup_is_unknown() {
+        let preflight = Preflight {
+            held_error: Some(String::from("dpkg-query failed")),
+            ..Preflight::passing()
+        };
+        assert_eq!(conflicts(&preflight), CheckStatus::Unknown);
+        assert!(preflight.holds_need_override());
+    }
+
+    #[test]
+    fn failed_essential_lookup_blocks() {
+        let preflight = Preflight {
+            removals: vec![String::from("libc6")],
+            essential_error: Some(String::from("dpkg-query failed")),
+            ..Preflight::passing()
+        };
+        assert_eq!(conflicts(&preflight), CheckStatus::Fail);
+        assert!(preflight.blocked());
+    }
+
+    #[test]
+    fn failed_architecture_lookup_is_unknown() {
+        let preflight = Preflight {
+            architecture_error: Some(String::from("dpkg failed")),
+            ..Preflight::passing()
+        };
+        assert_eq!(preflight.checks()[1].status, CheckStatus::Unknown);
+        assert!(!preflight.blocked());
+        assert!(preflight.needs_confirmation());
+    }
+}
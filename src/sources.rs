@@ -0,0 +1,101 @@
+//! Finds which apt source a repository package comes from.
+
+use std::collections::HashMap;
+use std::process::Command;
+
+use anyhow::bail;
+
+/// Hosts of the distributions' own archives. Packages from anywhere else are third-party.
+const OFFICIAL_HOSTS: &[&str] = &[
+    "archive.ubuntu.com",
+    "security.ubuntu.com",
+    "ports.ubuntu.com",
+    "deb.debian.org",
+    "security.debian.org",
+    "ftp.debian.org",
+    "apt.pop-os.org",
+];
+
+/// The apt source offering a package version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Origin {
+    /// URL of the archive, such as `http://archive.ubuntu.com/ubuntu`.
+    pub url: String,
+    /// Suite and component, such as `focal-updates/main`.
+    pub suite: String,
+}
+
+impl Origin {
+    fn host(&self) -> &str {
+        let rest = self
+            .url
+            .split_once("://")
+            .map_or(self.url.as_str(), |(_, rest)| rest);
+        rest.split(['/', ':']).next().unwrap_or_default()
+    }
+
+    /// Whether the source is a Launchpad PPA.
+    pub fn is_ppa(&self) -> bool {
+        self.host().starts_with("ppa.launchpad")
+    }
+
+    /// Whether the source isn't one of the distribution's own archives, including mirrors
+    /// such as `de.archive.ubuntu.com` or `ftp.fr.debian.org`.
+    pub fn is_third_party(&self) -> bool {
+        let host = self.host();
+        let mirror = host.starts_with("ftp.") && host.ends_with(".debian.org");
+        !mirror
+            && !OFFICIAL_HOSTS
+                .iter()
+                .any(|official| host == *official || host.ends_with(&format!(".{official}")))
+    }
+}
+
+/// Finds the sources of several package versions, keyed by PackageKit id.
+///
+/// Versions whose source can't be found are left out.
+pub fn origins<'a>(package_ids: impl IntoIterator<Item = &'a String>) -> HashMap<String, Origin> {
+    package_ids
+        .into_iter()
+        .filter_map(|package_id| match origin(package_id) {
+            Ok(origin) => origin.map(|origin| (package_id.clone(), origin)),
+            Err(why) => {
+                eprintln!("failed to find the source of {package_id}: {why}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Finds the source of a package version, given its PackageKit id.
+///
+/// Returns `None` when apt doesn't know the version, such as for local files.
+pub fn origin(package_id: &str) -> anyhow::Result<Option<Origin>> {
+    let mut parts = package_id.split(';');
+    let (name, version) = (
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or_default(),
+    );
+
+    let output = Command::new("apt-cache").args(["madison", name]).output()?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    // Lines look like `firefox | 89.0 | http://archive.ubuntu.com/ubuntu focal/main amd64 Packages`.
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| {
+            let mut columns = line.split('|').map(str::trim);
+            let (_name, line_version, source) = (columns.next()?, columns.next()?, columns.next()?);
+            if line_version != version || !source.ends_with("Packages") {
+                return None;
+            }
+
+            let mut words = source.split_whitespace();
+            Some(Origin {
+                url: words.next()?.to_string(),
+                suite: words.next()?.to_string(),
+            })
+        }))
+}
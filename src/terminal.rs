@@ -0,0 +1,58 @@
+//! Opens a terminal emulator following the package manager's log.
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+/// The log written by dpkg, which is readable without elevated privileges.
+const DPKG_LOG: &str = "/var/log/dpkg.log";
+
+/// Known terminal emulators, in order of preference, with the argument that precedes
+/// the command they should run.
+const TERMINALS: &[(&str, &str)] = &[
+    ("cosmic-term", "-e"),
+    ("x-terminal-emulator", "-e"),
+    ("gnome-terminal", "--"),
+    ("konsole", "-e"),
+    ("xfce4-terminal", "-x"),
+    ("alacritty", "-e"),
+    ("kitty", "--"),
+    ("foot", "--"),
+    ("xterm", "-e"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Terminal {
+    program: &'static str,
+    exec_arg: &'static str,
+}
+
+impl Terminal {
+    /// Finds the first known terminal emulator available in `PATH`.
+    pub fn detect() -> Option<Self> {
+        let path = env::var_os("PATH")?;
+        let dirs: Vec<_> = env::split_paths(&path).collect();
+
+        TERMINALS
+            .iter()
+            .find(|(program, _)| dirs.iter().any(|dir| is_executable(&dir.join(program))))
+            .map(|&(program, exec_arg)| Self { program, exec_arg })
+    }
+
+    /// Opens the terminal running `tail -f` on the dpkg log.
+    pub fn open_log(&self) -> anyhow::Result<()> {
+        Command::new(self.program)
+            .args([self.exec_arg, "tail", "-n", "20", "-f", DPKG_LOG])
+            .spawn()?;
+
+        Ok(())
+    }
+}
+
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
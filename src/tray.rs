@@ -0,0 +1,72 @@
+//! A status notifier tray icon showing install progress while the window is minimized.
+
+use std::fmt;
+
+use futures_util::channel::mpsc::UnboundedSender;
+
+use crate::fl;
+
+struct WizardTray {
+    progress: u32,
+    on_activate: UnboundedSender<()>,
+}
+
+impl ksni::Tray for WizardTray {
+    fn id(&self) -> String {
+        "io.github.cosmicUtils.Wizard".into()
+    }
+
+    fn title(&self) -> String {
+        fl!("app-title")
+    }
+
+    fn icon_name(&self) -> String {
+        "package-x-generic".into()
+    }
+
+    fn tool_tip(&self) -> ksni::ToolTip {
+        ksni::ToolTip {
+            title: fl!("app-title"),
+            description: fl!("tray-progress", progress = self.progress),
+            ..Default::default()
+        }
+    }
+
+    fn activate(&mut self, _x: i32, _y: i32) {
+        let _ = self.on_activate.unbounded_send(());
+    }
+}
+
+/// A handle to a running tray icon.
+#[derive(Clone)]
+pub struct TrayHandle(ksni::Handle<WizardTray>);
+
+impl TrayHandle {
+    /// Starts the tray icon, which sends to `on_activate` whenever it is clicked.
+    pub fn spawn(on_activate: UnboundedSender<()>) -> Self {
+        let service = ksni::TrayService::new(WizardTray {
+            progress: 0,
+            on_activate,
+        });
+        let handle = service.handle();
+        service.spawn();
+
+        Self(handle)
+    }
+
+    /// Updates the progress shown in the tooltip.
+    pub fn set_progress(&self, progress: u32) {
+        self.0.update(|tray| tray.progress = progress);
+    }
+
+    /// Removes the tray icon.
+    pub fn shutdown(&self) {
+        self.0.shutdown();
+    }
+}
+
+impl fmt::Debug for TrayHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrayHandle").finish_non_exhaustive()
+    }
+}